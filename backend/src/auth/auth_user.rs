@@ -29,18 +29,21 @@ impl<'r> FromRequest<'r> for AuthUser {
     type Error = Status;
 
     async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
-        if let Some(token) = req.cookies().get("token").map(|c| c.value().to_string()) {
-            if let Ok(claims) = get_claims(token) {
-                if claims.expires() >= chrono::Utc::now() {
-                    return Outcome::Success(AuthUser(claims.sub));
-                }
-            }
+        match request_claims(req) {
+            Some(claims) => Outcome::Success(AuthUser(claims.sub)),
+            None => Outcome::Error((Status::Unauthorized, Status::Unauthorized)),
         }
-        // TODO: Authorize user role or greater; create AuthAdmin guard for admin role
-        // authorization
+    }
+}
 
-        Outcome::Error((Status::Unauthorized, Status::Unauthorized))
+/// Returns the unexpired `Claims` from the request's token cookie, if any
+pub(super) fn request_claims(req: &Request<'_>) -> Option<Claims> {
+    let token = req.cookies().get("token").map(|c| c.value().to_string())?;
+    let claims = get_claims(token).ok()?;
+    if claims.expires() < chrono::Utc::now() {
+        return None;
     }
+    Some(claims)
 }
 
 impl<'a> OpenApiFromRequest<'a> for AuthUser {
@@ -49,30 +52,35 @@ impl<'a> OpenApiFromRequest<'a> for AuthUser {
         _name: String,
         _required: bool,
     ) -> rocket_okapi::Result<RequestHeaderInput> {
-        // Setup global requirement for Security scheme
-        let security_scheme = SecurityScheme {
-            description: Some(
-                "Requires an Bearer token to access, token is: `mytoken`.".to_owned(),
-            ),
-            // Setup data requirements.
-            // In this case the header `Authorization: mytoken` needs to be set.
-            data: SecuritySchemeData::Http {
-                scheme: "bearer".to_owned(), // `basic`, `digest`, ...
-                // Just gives use a hint to the format used
-                bearer_format: Some("bearer".to_owned()),
-            },
-            extensions: Object::default(),
-        };
-        // Add the requirement for this route/endpoint
-        // This can change between routes.
-        let mut security_req = SecurityRequirement::new();
-        // Each security requirement needs to be met before access is allowed.
-        security_req.insert("HttpAuth".to_owned(), Vec::new());
-        // These vvvvvvv-----^^^^^^^^ values need to match exactly!
-        Ok(RequestHeaderInput::Security(
-            "HttpAuth".to_owned(),
-            security_scheme,
-            security_req,
-        ))
+        security_input()
     }
 }
+
+/// OpenAPI security requirement shared by the authentication guards
+pub(super) fn security_input() -> rocket_okapi::Result<RequestHeaderInput> {
+    // Setup global requirement for Security scheme
+    let security_scheme = SecurityScheme {
+        description: Some(
+            "Requires an Bearer token to access, token is: `mytoken`.".to_owned(),
+        ),
+        // Setup data requirements.
+        // In this case the header `Authorization: mytoken` needs to be set.
+        data: SecuritySchemeData::Http {
+            scheme: "bearer".to_owned(), // `basic`, `digest`, ...
+            // Just gives use a hint to the format used
+            bearer_format: Some("bearer".to_owned()),
+        },
+        extensions: Object::default(),
+    };
+    // Add the requirement for this route/endpoint
+    // This can change between routes.
+    let mut security_req = SecurityRequirement::new();
+    // Each security requirement needs to be met before access is allowed.
+    security_req.insert("HttpAuth".to_owned(), Vec::new());
+    // These vvvvvvv-----^^^^^^^^ values need to match exactly!
+    Ok(RequestHeaderInput::Security(
+        "HttpAuth".to_owned(),
+        security_scheme,
+        security_req,
+    ))
+}
@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a proof-of-work challenge.
+/// A solution is any string `s` where the SHA-256 of `"<challenge>:<s>"` starts with
+/// `difficulty` zero bits. Send it as `X-Proof-Of-Work: <challenge>:<s>`. Each challenge is
+/// good for one request.
+#[derive(Serialize, JsonSchema)]
+pub struct Challenge {
+    pub challenge: String,
+    pub difficulty: u32,
+    pub expires: DateTime<Utc>,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents the claims of a proof-of-work challenge
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChallengeClaims {
+    /// `pow:<nonce>`, so a challenge can never pass for a user token
+    pub sub: String,
+    /// Always `token::CHALLENGE_AUDIENCE`
+    pub aud: String,
+    /// Expiration date of the challenge
+    pub exp: usize,
+    /// Leading zero bits a solution's hash needs
+    pub difficulty: u32,
+}
@@ -1,3 +1,4 @@
+use super::roles::Roles;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, TimeZone, Utc};
 
@@ -8,8 +9,14 @@ pub(super) struct Claims {
     pub sub: i32,
     /// Expiration date of the token
     pub exp: usize,
+    /// User role at the time the token was issued. Tokens issued before roles were carried in
+    /// the claims are treated as `Roles::Guest`.
+    #[serde(default = "guest")]
+    pub role: Roles,
 }
 
+fn guest() -> Roles { Roles::Guest }
+
 impl Claims {
     pub fn expires(&self) -> DateTime<Utc> {
         Utc.timestamp(self.exp as i64, 0)
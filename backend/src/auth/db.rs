@@ -46,8 +46,11 @@ pub(super) async fn create_user(
     .bind(Roles::Guest)
     .fetch_one(db.as_mut())
     .await
-    .map_err(|_| Status::InternalServerError)?; // TODO: Parse errors, tell client if user
-    // exists
+    .map_err(|e| match e {
+        // Usernames are unique
+        sqlx::Error::Database(ref d) if d.is_unique_violation() => Status::Conflict,
+        _ => Status::InternalServerError,
+    })?;
 
     Ok(row.get("id"))
 }
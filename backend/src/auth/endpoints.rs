@@ -1,12 +1,15 @@
 use super::auth_user::AuthUser;
+use super::challenge::Challenge;
 use super::db::create_user;
 use super::login_request::LoginRequest;
 use super::password::verify_password;
+use super::proof_of_work::ProofOfWork;
 use super::roles::Roles;
-use super::token::{create_jwt, get_claims};
+use super::token::{create_challenge_jwt, create_jwt, get_claims};
 use super::user::User;
 use super::cookie::{Expires, get_user_claims};
-use crate::db::BlogDB;
+use crate::config::config;
+use crate::db::{BlogDB, map_db_err};
 use rocket::{
     http::{Cookie, CookieJar, SameSite, Status},
     serde::json::Json,
@@ -14,29 +17,10 @@ use rocket::{
 use rocket_db_pools::{Connection, sqlx::{Row, Postgres}};
 use rocket_okapi::openapi;
 use chrono::{Utc, Duration};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-fn map_db_err(e: sqlx::Error) -> Status {
-    use std::io::ErrorKind;
-
-    match &e {
-        // No matching row for SELECT … FETCH_ONE
-        sqlx::Error::RowNotFound => Status::NotFound,
-
-        // Couldn’t reach the DB / pool timed out
-        sqlx::Error::Io(io) => {
-            if io.kind() == ErrorKind::TimedOut {
-                Status::ServiceUnavailable
-            } else {
-                Status::InternalServerError
-            }
-        }
-        sqlx::Error::PoolTimedOut => Status::ServiceUnavailable,
-        sqlx::Error::PoolClosed => Status::ServiceUnavailable,
-
-        // Anything else
-        _ => Status::InternalServerError,
-    }
-}
+/// How long a proof-of-work challenge can be solved and used
+const CHALLENGE_SECONDS: i64 = 300;
 
 /// Logs in a user given username and password. JWT token saved in browser cookies.
 #[openapi]
@@ -46,7 +30,7 @@ pub async fn login(
     jar: &CookieJar<'_>,
     mut db: Connection<BlogDB>,
 ) -> Result<Json<User>, Status> {
-    let row = sqlx::query("SELECT email, id, password, username FROM users WHERE email = $1")
+    let row = sqlx::query("SELECT email, id, password, username, role FROM users WHERE email = $1")
         .bind(&req.email)
         .fetch_one(&mut **db)
         .await
@@ -57,12 +41,13 @@ pub async fn login(
         username: row.get("username"),
     };
     let id = row.get("id");
+    let role: Roles = row.get("role");
     let password_hash = row.get("password");
     verify_password(password_hash, &req.password)?;
 
     // Password ok, continue login
     let expiration = Utc::now() + Duration::weeks(1);
-    let token = create_jwt(id, expiration).map_err(|_| Status::InternalServerError)?;
+    let token = create_jwt(id, role, expiration).map_err(|_| Status::InternalServerError)?;
     // Save the token as a cookie
     // Set as HttpOnly, Secure, SameSite <- Security features, change with caution!
     let mut cookie = Cookie::new("token", token);
@@ -88,14 +73,40 @@ pub async fn logout(jar: &CookieJar<'_>) -> Result<Json<String>, Status> {
     Ok(Json("ok".into()))
 }
 
-/// Adds a new user to the database
+/// Adds a new user to the database. Requires a solved challenge from `/challenge`.
 #[openapi]
 #[post("/signup", data = "<req>")]
-pub async fn signup(req: Json<LoginRequest>, mut db: Connection<BlogDB>) -> Result<(), Status> {
+pub async fn signup(
+    _pow: ProofOfWork,
+    req: Json<LoginRequest>,
+    mut db: Connection<BlogDB>,
+) -> Result<(), Status> {
     let _ = create_user(req, &mut db).await?;
     Ok(())
 }
 
+/// Issues a signed proof-of-work challenge. Solving it is required by endpoints that bots
+/// would otherwise flood, such as `/signup` and `/contact`.
+#[openapi]
+#[get("/challenge")]
+pub async fn challenge() -> Result<Json<Challenge>, Status> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    // Unique per challenge; the signature already stops clients from making their own
+    let now = Utc::now();
+    let nonce = format!(
+        "{:x}{:x}",
+        now.timestamp_nanos_opt().unwrap_or_default(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+    );
+    let difficulty = config().pow.difficulty;
+    let expires = now + Duration::seconds(CHALLENGE_SECONDS);
+    let challenge = create_challenge_jwt(&nonce, difficulty, expires)
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(Challenge { challenge, difficulty, expires }))
+}
+
 /// Creates an admin user account. Only compiled with `debug_assertions`. VERIFY NO ENDPOINT IN
 /// RELEASE CODE!!!
 #[openapi]
@@ -131,7 +142,7 @@ pub async fn me(
     let (claims, expires) = get_user_claims(&jar)?;
 
     // Get the user data
-    let row = sqlx::query("SELECT email, username FROM users WHERE id = $1")
+    let row = sqlx::query("SELECT email, username, role FROM users WHERE id = $1")
         .bind(claims.sub)
         .fetch_one(&mut **db)
         .await
@@ -142,9 +153,10 @@ pub async fn me(
         username: row.get("username"),
     };
 
-    // Regenerate the token
+    // Regenerate the token, picking up any role change since it was issued
+    let role: Roles = row.get("role");
     let expiration = Utc::now() + Duration::weeks(1);
-    let token = create_jwt(claims.sub, expiration).map_err(|_| Status::InternalServerError)?;
+    let token = create_jwt(claims.sub, role, expiration).map_err(|_| Status::InternalServerError)?;
 
     // Save the cookie with the new information
     let mut cookie = Cookie::new("token", token);
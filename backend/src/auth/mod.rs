@@ -1,17 +1,35 @@
 mod auth_user;
+mod challenge;
+mod challenge_claims;
 mod claims;
 mod db;
 mod endpoints;
 mod login_request;
 mod password;
+mod preview_claims;
+mod proof_of_work;
+mod require_role;
 mod roles;
 mod token;
+mod unlock_claims;
 mod user;
 mod cookie;
 mod link;
 
 pub use auth_user::AuthUser;
+pub use challenge::Challenge;
+pub use challenge_claims::ChallengeClaims;
 pub use db::authorize_role;
 pub use endpoints::*;
 pub use login_request::LoginRequest;
+pub use password::{hash_password, verify_password};
+pub use preview_claims::PreviewClaims;
+pub use proof_of_work::{PROOF_OF_WORK_HEADER, ProofOfWork};
+pub use require_role::{Admin, Author, Guest, RequireRole, RoleLevel};
 pub use roles::Roles;
+pub use token::{
+    CHALLENGE_AUDIENCE, PREVIEW_AUDIENCE, UNLOCK_AUDIENCE, create_challenge_jwt,
+    create_preview_jwt, create_unlock_jwt, get_challenge_claims, get_preview_claims,
+    get_unlock_claims,
+};
+pub use unlock_claims::UnlockClaims;
@@ -6,7 +6,7 @@ use argon2::{
 };
 
 /// Hashes password
-pub(super) fn hash_password(password: &str) -> String {
+pub fn hash_password(password: &str) -> String {
     let salt = SaltString::generate(&mut OsRng);
     Argon2::default()
         .hash_password(password.as_bytes(), &salt)
@@ -15,7 +15,7 @@ pub(super) fn hash_password(password: &str) -> String {
 }
 
 /// Compares a password hash to an unhashed password for verification
-pub(super) fn verify_password(hash: &str, password: &str) -> Result<(), Status> {
+pub fn verify_password(hash: &str, password: &str) -> Result<(), Status> {
     let parsed_hash = PasswordHash::new(hash).unwrap();
     if !Argon2::default()
         .verify_password(password.as_bytes(), &parsed_hash)
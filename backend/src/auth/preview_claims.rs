@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents the claims of a draft preview token
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PreviewClaims {
+    /// `post:<id>`, so a preview token can never pass for a user token
+    pub sub: String,
+    /// Always `token::PREVIEW_AUDIENCE`
+    pub aud: String,
+    /// Expiration date of the token
+    pub exp: usize,
+    /// Preview token ID, used to check for revocation
+    pub jti: String,
+}
+
+impl PreviewClaims {
+    /// ID of the post the token previews
+    pub fn post_id(&self) -> Option<i32> {
+        self.sub.strip_prefix("post:")?.parse().ok()
+    }
+
+    /// ID of the token in the database
+    pub fn token_id(&self) -> Option<i32> {
+        self.jti.parse().ok()
+    }
+}
@@ -0,0 +1,134 @@
+use super::challenge_claims::ChallengeClaims;
+use super::token::get_challenge_claims;
+use crate::config::config;
+use crate::db::{BlogDB, map_db_err};
+use rocket::{
+    http::Status,
+    request::{self, FromRequest, Outcome, Request},
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+    r#gen::OpenApiGenerator,
+    okapi::openapi3::{Object, Parameter, ParameterValue},
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+use sha2::{Digest, Sha256};
+
+/// Header carrying a solved challenge, as `<challenge>:<solution>`
+pub const PROOF_OF_WORK_HEADER: &str = "X-Proof-Of-Work";
+
+/// Longest solution accepted, so hashing stays cheap for the server
+const MAX_SOLUTION_LENGTH: usize = 64;
+
+/// Requires a solved proof-of-work challenge from `/challenge` on the request. Challenges are
+/// checked against their signature alone, but each can only be used once: used ones are kept in
+/// `spent_challenge` until they expire, so every protected request costs a fresh solution.
+///
+/// # Example
+/// ```rust
+/// #[post("/protected")]
+/// async fn protected(_pow: ProofOfWork) -> Json<String> {
+///     Json("ok".into())
+/// }
+/// ```
+pub struct ProofOfWork;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ProofOfWork {
+    type Error = Status;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let Some(claims) = req.headers().get_one(PROOF_OF_WORK_HEADER).and_then(verify) else {
+            return Outcome::Error((Status::Forbidden, Status::Forbidden));
+        };
+        let Outcome::Success(mut db) = req.guard::<Connection<BlogDB>>().await else {
+            return Outcome::Error((Status::ServiceUnavailable, Status::ServiceUnavailable));
+        };
+
+        match spend(&mut db, &claims).await {
+            Ok(true) => Outcome::Success(ProofOfWork),
+            Ok(false) => Outcome::Error((Status::Forbidden, Status::Forbidden)),
+            Err(e) => Outcome::Error((e, e)),
+        }
+    }
+}
+
+/// Checks a `<challenge>:<solution>` header value
+/// # Returns
+/// - `Option<ChallengeClaims>` - Claims of the challenge if it is signed, unexpired, hard enough
+///   and solved
+fn verify(value: &str) -> Option<ChallengeClaims> {
+    let (challenge, solution) = value.rsplit_once(':')?;
+    if solution.len() > MAX_SOLUTION_LENGTH {
+        return None;
+    }
+    let claims = get_challenge_claims(challenge).ok()?;
+    // Challenges issued before the difficulty was raised are not good enough any more
+    if claims.difficulty < config().pow.difficulty {
+        return None;
+    }
+    if leading_zero_bits(&Sha256::digest(value.as_bytes())) < claims.difficulty {
+        return None;
+    }
+    Some(claims)
+}
+
+/// Marks a challenge as used, dropping used challenges that have expired since
+/// # Arguments
+/// - `db`: `&mut Connection<BlogDB>` - Rocket Sqlx_pools DB
+/// - `claims`: `&ChallengeClaims` - Verified claims of the challenge
+/// # Returns
+/// - `Result<bool, Status>` - `false` if the challenge was already used
+async fn spend(db: &mut Connection<BlogDB>, claims: &ChallengeClaims) -> Result<bool, Status> {
+    let spent = sqlx::query(
+        "WITH pruned AS (DELETE FROM spent_challenge WHERE expires_at < NOW()) \
+        INSERT INTO spent_challenge (sub, expires_at) VALUES ($1, to_timestamp($2::bigint)) \
+        ON CONFLICT (sub) DO NOTHING",
+    )
+    .bind(&claims.sub)
+    .bind(claims.exp as i64)
+    .execute(&mut ***db)
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(spent.rows_affected() == 1)
+}
+
+fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+impl<'a> OpenApiFromRequest<'a> for ProofOfWork {
+    fn from_request_input(
+        generator: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::Parameter(Parameter {
+            name: PROOF_OF_WORK_HEADER.to_owned(),
+            location: "header".to_owned(),
+            description: Some(
+                "Solved challenge from `/challenge`, as `<challenge>:<solution>`".to_owned(),
+            ),
+            required: true,
+            deprecated: false,
+            allow_empty_value: false,
+            value: ParameterValue::Schema {
+                style: None,
+                explode: None,
+                allow_reserved: false,
+                schema: generator.json_schema::<String>(),
+                example: None,
+                examples: None,
+            },
+            extensions: Object::default(),
+        }))
+    }
+}
@@ -0,0 +1,96 @@
+use super::auth_user::{request_claims, security_input};
+use super::roles::Roles;
+use rocket::{
+    http::Status,
+    request::{self, FromRequest, Outcome, Request},
+};
+use rocket_okapi::{
+    r#gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+use std::marker::PhantomData;
+
+/// Minimum role required by a `RequireRole` guard
+pub trait RoleLevel: Send + Sync + 'static {
+    const ROLE: Roles;
+}
+
+/// Requires any signed-in user
+pub struct Guest;
+
+impl RoleLevel for Guest {
+    const ROLE: Roles = Roles::Guest;
+}
+
+/// Requires `Roles::Author` or better
+pub struct Author;
+
+impl RoleLevel for Author {
+    const ROLE: Roles = Roles::Author;
+}
+
+/// Requires `Roles::Admin`
+pub struct Admin;
+
+impl RoleLevel for Admin {
+    const ROLE: Roles = Roles::Admin;
+}
+
+/// Authorizes a user with the given role or better for endpoint request. The role is read from
+/// the token claims, so no database round-trip is needed.
+///
+/// # Example
+/// ```rust
+/// // Endpoint for authors and admins
+/// #[get("/protected")]
+/// async fn protected(user: RequireRole<Author>) -> Json<i32> {
+///     Json(user.id()) // user id
+/// }
+/// ```
+pub struct RequireRole<R: RoleLevel> {
+    id: i32,
+    role: Roles,
+    level: PhantomData<R>,
+}
+
+impl<R: RoleLevel> RequireRole<R> {
+    /// User ID
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    /// Role the user actually holds, which may be better than the required one
+    pub fn role(&self) -> Roles {
+        self.role
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, R: RoleLevel> FromRequest<'r> for RequireRole<R> {
+    type Error = Status;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let Some(claims) = request_claims(req) else {
+            return Outcome::Error((Status::Unauthorized, Status::Unauthorized));
+        };
+        if let Err(status) = claims.role.authorize(R::ROLE) {
+            return Outcome::Error((status, status));
+        }
+
+        Outcome::Success(RequireRole {
+            id: claims.sub,
+            role: claims.role,
+            level: PhantomData,
+        })
+    }
+}
+
+impl<'a, R: RoleLevel> OpenApiFromRequest<'a> for RequireRole<R> {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        security_input()
+    }
+}
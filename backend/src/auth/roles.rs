@@ -4,9 +4,9 @@ use rocket::http::Status;
 use std::cmp::Ordering;
 
 /// User roles, used for authenticated access to certain endpoints
-#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema)]
-#[sqlx(type_name = "roles")] // Must match Postgres enum name
-#[sqlx(rename_all = "PascalCase")] // Must match Postgres variant case
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema)]
+#[sqlx(type_name = "user_role")] // Must match Postgres enum name
+#[sqlx(rename_all = "lowercase")] // Must match Postgres variant case
 pub enum Roles {
     Admin,
     Author,
@@ -14,8 +14,9 @@ pub enum Roles {
 }
 
 impl Roles {
+    /// Returns `Ok` if this role is `comp_role` or better
     pub fn authorize(&self, comp_role: Self) -> Result<(), Status> {
-        if comp_role < *self {
+        if *self < comp_role {
             return Err(Status::Unauthorized);
         }
         Ok(())
@@ -1,4 +1,8 @@
+use super::challenge_claims::ChallengeClaims;
 use super::claims::Claims;
+use super::preview_claims::PreviewClaims;
+use super::unlock_claims::UnlockClaims;
+use super::roles::Roles;
 use crate::config::config;
 use jsonwebtoken::{EncodingKey, DecodingKey, Header, encode, decode, errors::Error, Validation, Algorithm };
 use chrono::{DateTime, Utc};
@@ -6,11 +10,13 @@ use chrono::{DateTime, Utc};
 /// Creates a JWT token
 /// # Arguments
 /// - `user_id`: `i32` - user ID (from database)
+/// - `role`: `auth::Roles` - user role (from database)
 /// - `expires`: `chrono::DateTime<Utc>` - Token expiration date
-pub(super) fn create_jwt(user_id: i32, expires: DateTime<Utc>) -> Result<String, Error> {
+pub(super) fn create_jwt(user_id: i32, role: Roles, expires: DateTime<Utc>) -> Result<String, Error> {
     let claims = Claims {
         sub: user_id,
         exp: expires.timestamp() as usize,
+        role,
     };
     println!("Create jwt: {:?}", claims);
     encode(
@@ -28,3 +34,104 @@ pub(super) fn get_claims(token: String) -> Result<Claims, Error> {
         &Validation::default(),
     )?.claims)
 }
+
+/// Audience of draft preview tokens. User tokens have none, so neither kind passes for the other.
+pub const PREVIEW_AUDIENCE: &str = "preview";
+
+/// Audience of password-protected post unlock tokens
+pub const UNLOCK_AUDIENCE: &str = "unlock";
+
+/// Audience of proof-of-work challenges
+pub const CHALLENGE_AUDIENCE: &str = "challenge";
+
+/// Creates a JWT that lets anyone holding it read an unpublished post
+/// # Arguments
+/// - `post_id`: `i32` - Post to preview
+/// - `token_id`: `i32` - Preview token ID (from database), checked for revocation on use
+/// - `expires`: `chrono::DateTime<Utc>` - Token expiration date
+pub fn create_preview_jwt(post_id: i32, token_id: i32, expires: DateTime<Utc>) -> Result<String, Error> {
+    let claims = PreviewClaims {
+        sub: format!("post:{post_id}"),
+        aud: PREVIEW_AUDIENCE.into(),
+        exp: expires.timestamp() as usize,
+        jti: token_id.to_string(),
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config().secret.as_ref()),
+    )
+}
+
+/// Returns the `auth::PreviewClaims` encoded in a preview JWT
+pub fn get_preview_claims(token: &str) -> Result<PreviewClaims, Error> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_audience(&[PREVIEW_AUDIENCE]);
+    Ok(decode::<PreviewClaims>(
+        token,
+        &DecodingKey::from_secret(config().secret.as_ref()),
+        &validation,
+    )?.claims)
+}
+
+/// Creates a JWT that unlocks a password-protected post
+/// # Arguments
+/// - `post_id`: `i32` - Post to unlock
+/// - `key`: `&str` - Fingerprint of the post's password hash
+/// - `expires`: `chrono::DateTime<Utc>` - Token expiration date
+pub fn create_unlock_jwt(post_id: i32, key: &str, expires: DateTime<Utc>) -> Result<String, Error> {
+    let claims = UnlockClaims {
+        sub: format!("post:{post_id}"),
+        aud: UNLOCK_AUDIENCE.into(),
+        exp: expires.timestamp() as usize,
+        key: key.into(),
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config().secret.as_ref()),
+    )
+}
+
+/// Returns the `auth::UnlockClaims` encoded in an unlock JWT
+pub fn get_unlock_claims(token: &str) -> Result<UnlockClaims, Error> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_audience(&[UNLOCK_AUDIENCE]);
+    Ok(decode::<UnlockClaims>(
+        token,
+        &DecodingKey::from_secret(config().secret.as_ref()),
+        &validation,
+    )?.claims)
+}
+
+/// Creates a signed proof-of-work challenge
+/// # Arguments
+/// - `nonce`: `&str` - Unique value making the challenge impossible to solve ahead of time
+/// - `difficulty`: `u32` - Leading zero bits a solution's hash needs
+/// - `expires`: `chrono::DateTime<Utc>` - Challenge expiration date
+pub fn create_challenge_jwt(nonce: &str, difficulty: u32, expires: DateTime<Utc>) -> Result<String, Error> {
+    let claims = ChallengeClaims {
+        sub: format!("pow:{nonce}"),
+        aud: CHALLENGE_AUDIENCE.into(),
+        exp: expires.timestamp() as usize,
+        difficulty,
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config().secret.as_ref()),
+    )
+}
+
+/// Returns the `auth::ChallengeClaims` encoded in a challenge JWT
+pub fn get_challenge_claims(token: &str) -> Result<ChallengeClaims, Error> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_audience(&[CHALLENGE_AUDIENCE]);
+    // No leeway, so a challenge is refused as soon as it is forgotten as spent
+    validation.leeway = 0;
+    Ok(decode::<ChallengeClaims>(
+        token,
+        &DecodingKey::from_secret(config().secret.as_ref()),
+        &validation,
+    )?.claims)
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents the claims of a token unlocking a password-protected post
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnlockClaims {
+    /// `post:<id>`, so an unlock token can never pass for a user token
+    pub sub: String,
+    /// Always `token::UNLOCK_AUDIENCE`
+    pub aud: String,
+    /// Expiration date of the token
+    pub exp: usize,
+    /// Fingerprint of the password hash, so changing the password locks the post again
+    pub key: String,
+}
+
+impl UnlockClaims {
+    /// ID of the post the token unlocks
+    pub fn post_id(&self) -> Option<i32> {
+        self.sub.strip_prefix("post:")?.parse().ok()
+    }
+}
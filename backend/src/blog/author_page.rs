@@ -0,0 +1,11 @@
+use super::post_summary::PostSummary;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a public author page
+#[derive(Serialize, JsonSchema)]
+pub struct AuthorPage {
+    pub username: String,
+    /// Published posts written or co-written by the author, newest first
+    pub posts: Vec<PostSummary>,
+}
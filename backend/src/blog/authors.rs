@@ -0,0 +1,35 @@
+use super::author_page::AuthorPage;
+use super::post_summary::PostSummary;
+use super::visibility::met_roles;
+use crate::auth;
+use crate::db::{BlogDB, map_db_err};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::Connection;
+use rocket_okapi::openapi;
+
+/// Lists the published posts of an author, including posts they co-wrote
+#[openapi]
+#[get("/author/<username>")]
+pub async fn author(
+    username: String,
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<AuthorPage>, Status> {
+    let author_id: i32 = sqlx::query_scalar("SELECT id FROM users WHERE username = $1")
+        .bind(&username)
+        .fetch_one(&mut **db)
+        .await
+        .map_err(map_db_err)?;
+
+    let posts = sqlx::query("SELECT * FROM get_author_posts($1, $2)")
+        .bind(author_id)
+        .bind(met_roles(user.map(|u| u.role())))
+        .fetch_all(&mut **db)
+        .await
+        .map_err(map_db_err)?
+        .iter()
+        .map(PostSummary::from_row)
+        .collect();
+
+    Ok(Json(AuthorPage { username, posts }))
+}
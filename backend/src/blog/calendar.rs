@@ -0,0 +1,183 @@
+use super::publish_calendar::{CalendarEntry, CalendarGap, PublishCalendar};
+use super::publish_event::PublishSource;
+use crate::auth;
+use crate::config::config;
+use crate::db::{BlogDB, map_db_err};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+
+/// Range shown when no end date is given
+const DEFAULT_DAYS: i64 = 30;
+
+/// Longest range that can be requested
+const MAX_DAYS: i64 = 366;
+
+/// Most runs projected for a single queue, enough for an hourly queue over `MAX_DAYS`. Runs
+/// past it are left out of the calendar.
+const MAX_RUNS_PER_QUEUE: usize = 10_000;
+
+/// A projected run of a publish queue
+struct QueueRun {
+    at: DateTime<Utc>,
+    queue_id: i32,
+    category: Option<String>,
+    tag: Option<String>,
+}
+
+/// Projects when every pending post will be published, from explicit publish dates and from
+/// the active publish queues each working through the queued posts that match them
+/// # Arguments
+/// - `from`: `Option<&str>` - First day of the range as `YYYY-MM-DD`, in the site timezone.
+///   Defaults to today.
+/// - `to`: `Option<&str>` - Last day of the range as `YYYY-MM-DD`. Defaults to 30 days after
+///   `from`, and may be at most 366 days after it.
+#[openapi]
+#[get("/admin/calendar?<from>&<to>")]
+pub async fn calendar(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Json<PublishCalendar>, Status> {
+    // Database time, since that is what the scheduler compares against
+    let now: DateTime<Utc> = sqlx::query("SELECT NOW() AS now")
+        .fetch_one(&mut **db)
+        .await
+        .map_err(map_db_err)?
+        .get("now");
+
+    let from = match from {
+        Some(d) => parse_date(d)?,
+        None => now.with_timezone(&config().site.timezone).date_naive(),
+    };
+    let to = match to {
+        Some(d) => parse_date(d)?,
+        None => from + Duration::days(DEFAULT_DAYS),
+    };
+    if to < from || to - from > Duration::days(MAX_DAYS) {
+        return Err(Status::BadRequest);
+    }
+    let range_start = start_of_day(from);
+    let range_end = start_of_day(to + Duration::days(1));
+
+    let mut entries: Vec<CalendarEntry> = Vec::new();
+
+    // Scheduled posts go out on the first tick after their publish date
+    let scheduled = sqlx::query(
+        "SELECT id, slug, title, publish_date FROM post WHERE status = 'scheduled'",
+    )
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+    for r in scheduled {
+        let date: Option<DateTime<Utc>> = r.get("publish_date");
+        entries.push(CalendarEntry {
+            post_id: r.get("id"),
+            slug: r.get("slug"),
+            title: r.get("title"),
+            publish_date: date.unwrap_or(now).max(now),
+            source: PublishSource::Scheduled,
+            queue_id: None,
+        });
+    }
+
+    // Queued posts in the order the queues will take them, with what queues match them on
+    let mut queued: Vec<(CalendarEntry, String, Vec<String>)> = sqlx::query(
+        "SELECT p.id, p.slug, p.title, p.category, \
+        ARRAY(SELECT t.tag_slug FROM post_tag AS t WHERE t.post_id = p.id) AS tags \
+        FROM post AS p \
+        WHERE p.status = 'queued' \
+        ORDER BY p.queue_position NULLS LAST, p.upload_date",
+    )
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .into_iter()
+    .map(|r| {
+        let entry = CalendarEntry {
+            post_id: r.get("id"),
+            slug: r.get("slug"),
+            title: r.get("title"),
+            publish_date: now,
+            source: PublishSource::Queue,
+            queue_id: None,
+        };
+        (entry, r.get("category"), r.get("tags"))
+    })
+    .collect();
+
+    // Every queue run from now until the end of the range, in the order they happen. An
+    // overdue queue catches up one interval per tick, which is as good as now.
+    let queues = sqlx::query(
+        "SELECT id, next_run_at, EXTRACT(EPOCH FROM run_interval)::bigint AS run_interval, \
+        category, tag_slug \
+        FROM publish_queue WHERE active = true",
+    )
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+    let mut runs: Vec<QueueRun> = Vec::new();
+    for r in queues {
+        let interval = Duration::seconds(r.get::<i64, _>("run_interval"));
+        if interval <= Duration::zero() {
+            continue;
+        }
+        let category: Option<String> = r.get("category");
+        let tag: Option<String> = r.get("tag_slug");
+        let mut run: DateTime<Utc> = r.get("next_run_at");
+        let mut count = 0;
+        while run < range_end && count < MAX_RUNS_PER_QUEUE {
+            runs.push(QueueRun {
+                at: run.max(now),
+                queue_id: r.get("id"),
+                category: category.clone(),
+                tag: tag.clone(),
+            });
+            run += interval;
+            count += 1;
+        }
+    }
+    runs.sort_by_key(|run| (run.at, run.queue_id));
+
+    let mut gaps: Vec<CalendarGap> = Vec::new();
+    for run in runs {
+        let next = queued.iter().position(|(_, category, tags)| {
+            run.category.as_ref().is_none_or(|c| c == category)
+                && run.tag.as_ref().is_none_or(|t| tags.contains(t))
+        });
+        match next {
+            Some(i) => {
+                let (mut entry, _, _) = queued.remove(i);
+                entry.publish_date = run.at;
+                entry.queue_id = Some(run.queue_id);
+                entries.push(entry);
+            }
+            None => gaps.push(CalendarGap { queue_id: run.queue_id, run_at: run.at }),
+        }
+    }
+
+    entries.retain(|e| e.publish_date >= range_start && e.publish_date < range_end);
+    entries.sort_by_key(|e| e.publish_date);
+    gaps.retain(|g| g.run_at >= range_start);
+
+    Ok(Json(PublishCalendar { from, to, entries, gaps }))
+}
+
+/// Returns when a day starts in the site timezone
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    config()
+        .site
+        .timezone
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        // Midnight skipped by a DST change
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, Status> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| Status::BadRequest)
+}
@@ -0,0 +1,228 @@
+use super::links::set_links;
+use super::obsidian::strip_comments;
+use super::tags::set_tags;
+use super::upload::{parse_front_matter, render_body, split_front_matter};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rocket_db_pools::sqlx::Row;
+use sqlx::PgConnection;
+use std::collections::{HashMap, HashSet};
+
+/// How many levels of `![[embeds]]` are inlined before falling back to a plain link
+pub(super) const MAX_EMBED_DEPTH: usize = 3;
+
+/// Matches `![[target#section|text]]`, where the section and text are optional
+static EMBED_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"!\[\[([^\]\|#]+)(?:#([^\]\|]+))?(?:\|([^\]]+))?\]\]").unwrap());
+
+/// Markdown sources of embedded posts, keyed by slug
+pub(super) type EmbedSources = HashMap<String, String>;
+
+/// Returns the slugs of the posts embedded in the markdown
+pub(super) fn embed_targets(md: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for caps in EMBED_RE.captures_iter(md) {
+        let slug = slug::slugify(caps.get(1).unwrap().as_str().trim());
+        if !targets.contains(&slug) {
+            targets.push(slug);
+        }
+    }
+    targets
+}
+
+/// Fetches the markdown source of every post embedded in `md`, following nested embeds up to
+/// `MAX_EMBED_DEPTH` levels deep. Missing, unpublished, members-only and password-protected
+/// posts are left out, so their content can't leak into more public posts.
+pub(super) async fn load_embeds(
+    db: &mut PgConnection,
+    md: &str,
+) -> Result<EmbedSources, String> {
+    let mut sources = EmbedSources::new();
+    let mut pending = embed_targets(md);
+
+    for _ in 0..MAX_EMBED_DEPTH {
+        pending.retain(|slug| !sources.contains_key(slug));
+        if pending.is_empty() {
+            break;
+        }
+
+        let rows = sqlx::query(
+            "SELECT slug, source FROM post \
+            WHERE slug = ANY($1) AND source IS NOT NULL AND status = 'published' \
+            AND visibility <> 'members' AND password_hash IS NULL",
+        )
+        .bind(&pending)
+        .fetch_all(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+        pending = Vec::new();
+        for row in rows {
+            let slug: String = row.get("slug");
+            let source: String = row.get("source");
+            pending.extend(embed_targets(&source));
+            sources.insert(slug, source);
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Inlines `![[embeds]]` with the markdown of the embedded post, or one section of it. Embeds
+/// that are missing, cyclic or deeper than `MAX_EMBED_DEPTH` become plain links.
+/// # Arguments
+/// - `md`: `&str` - Markdown body, without front matter
+/// - `sources`: `&EmbedSources` - Sources loaded by `load_embeds`
+/// - `stack`: `&mut Vec<String>` - Slugs of the posts currently being inlined, used for cycle
+///   detection
+pub(super) fn transclude(md: &str, sources: &EmbedSources, stack: &mut Vec<String>) -> String {
+    EMBED_RE.replace_all(md, |caps: &regex::Captures| {
+        let target = caps.get(1).unwrap().as_str().trim();
+        let section = caps.get(2).map(|m| m.as_str().trim());
+        let slug = slug::slugify(target);
+        let fallback = {
+            let text = caps.get(3).map(|m| m.as_str()).unwrap_or(target);
+            format!("[{text}](/blog/{slug})")
+        };
+
+        if stack.len() >= MAX_EMBED_DEPTH || stack.contains(&slug) {
+            return fallback;
+        }
+        let Some(source) = sources.get(&slug) else {
+            return fallback;
+        };
+        let body = match split_front_matter(source) {
+            Ok((_, body)) => body,
+            Err(_) => return fallback,
+        };
+        let body = match section {
+            Some(heading) => match extract_section(body, heading) {
+                Some(s) => s,
+                None => return fallback,
+            },
+            None => body,
+        };
+
+        stack.push(slug);
+        let inlined = transclude(&strip_comments(body), sources, stack);
+        stack.pop();
+
+        // Blank lines keep the embed from running into the surrounding paragraph
+        format!("\n\n{}\n\n", inlined.trim())
+    }).into_owned()
+}
+
+/// Returns the section of `md` under the heading matching `heading`, up to the next heading of
+/// the same or higher level. Headings are compared by slug.
+fn extract_section<'a>(md: &'a str, heading: &str) -> Option<&'a str> {
+    let wanted = slug::slugify(heading);
+    let mut start: Option<(usize, usize)> = None; // (byte offset, heading level)
+    let mut offset = 0;
+
+    for line in md.split_inclusive('\n') {
+        let trimmed = line.trim();
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        let is_heading = level > 0 && trimmed[level..].starts_with(' ');
+
+        if is_heading {
+            match start {
+                Some((begin, start_level)) if level <= start_level => {
+                    return Some(&md[begin..offset]);
+                }
+                None if slug::slugify(&trimmed[level..]) == wanted => {
+                    start = Some((offset, level));
+                }
+                _ => {}
+            }
+        }
+        offset += line.len();
+    }
+
+    start.map(|(begin, _)| &md[begin..])
+}
+
+/// Re-renders every post that embeds the given post, directly or through other embeds, and
+/// flags them in `updated_post`
+pub(super) async fn rerender_embedders(
+    db: &mut PgConnection,
+    slug: &str,
+) -> Result<(), String> {
+    let mut seen: HashSet<i32> = HashSet::new();
+    let mut pending = vec![slug.to_string()];
+
+    for _ in 0..MAX_EMBED_DEPTH {
+        if pending.is_empty() {
+            break;
+        }
+
+        let rows = sqlx::query(
+            "SELECT DISTINCT p.id, p.slug, p.source FROM post_embed AS e \
+            JOIN post AS p ON e.source_id = p.id \
+            WHERE e.target_slug = ANY($1) AND p.source IS NOT NULL",
+        )
+        .bind(&pending)
+        .fetch_all(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+        pending = Vec::new();
+        for row in rows {
+            let id: i32 = row.get("id");
+            if !seen.insert(id) {
+                continue;
+            }
+            let source: String = row.get("source");
+            let (meta, body) = parse_front_matter(&source)?;
+            let embeds = load_embeds(db, body).await?;
+            let rendered = render_body(body, &meta, &embeds);
+
+            sqlx::query(
+                "UPDATE post SET body = $1, word_count = $2, reading_time = $3, excerpt = $4 \
+                WHERE id = $5",
+            )
+            .bind(&rendered.html)
+            .bind(rendered.stats.word_count)
+            .bind(rendered.stats.reading_time)
+            .bind(&rendered.stats.excerpt)
+            .bind(id)
+            .execute(&mut *db)
+            .await
+            .map_err(|e| format!("database error: {e}"))?;
+            set_links(db, id, &rendered.links).await?;
+            set_embeds(db, id, &rendered.embeds).await?;
+            set_tags(db, id, &rendered.tags).await?;
+
+            sqlx::query("INSERT INTO updated_post (post_id) VALUES ($1)")
+                .bind(id)
+                .execute(&mut *db)
+                .await
+                .map_err(|e| format!("database error: {e}"))?;
+
+            pending.push(row.get("slug"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces the embeds recorded for a post
+pub(super) async fn set_embeds(
+    db: &mut PgConnection,
+    post_id: i32,
+    embeds: &[String],
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM post_embed WHERE source_id = $1")
+        .bind(post_id)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    sqlx::query("INSERT INTO post_embed (source_id, target_slug) SELECT $1, UNNEST($2::text[])")
+        .bind(post_id)
+        .bind(embeds)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    Ok(())
+}
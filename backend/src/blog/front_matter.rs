@@ -0,0 +1,99 @@
+use super::post_visibility::PostVisibility;
+use crate::auth::Roles;
+use crate::config::config;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, de};
+
+/// Represents the YAML front matter of an uploaded post
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct FrontMatter {
+    pub title: Option<String>,
+    pub category: Option<String>,
+    /// Hand-written summary, used instead of the automatic excerpt
+    pub summary: Option<String>,
+    /// Social media description. Falls back to the excerpt.
+    pub description: Option<String>,
+    /// Social media image URL. A card is generated when missing.
+    pub cover_image: Option<String>,
+    /// Canonical URL, for posts first published elsewhere
+    pub canonical_url: Option<String>,
+    /// Dates without an offset, such as `2026-11-01 09:00`, are in the site timezone
+    #[serde(default, deserialize_with = "site_date")]
+    pub publish_date: Option<DateTime<Utc>>,
+    /// When the post is archived, for time-limited posts like event announcements
+    #[serde(default, deserialize_with = "site_date")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When the post should be checked for being out of date. Readers are warned after it passes.
+    #[serde(default, deserialize_with = "site_date")]
+    pub review_by: Option<DateTime<Utc>>,
+    /// Usernames credited alongside the uploading author
+    #[serde(default)]
+    pub coauthors: Vec<String>,
+    /// Title of the series the post belongs to
+    pub series: Option<String>,
+    /// Position within the series. Appended to the end when missing.
+    pub series_order: Option<i32>,
+    /// Who can find and read the post. Defaults to `public`.
+    pub visibility: Option<PostVisibility>,
+    /// Minimum role for `members` posts: `guest`, `author` or `admin`. Defaults to any signed-in
+    /// user.
+    #[serde(default, deserialize_with = "role_name")]
+    pub min_role: Option<Roles>,
+    /// Shared password readers must enter to see the post. Only its hash is stored. When editing,
+    /// leaving it out keeps the current password and an empty string removes it.
+    pub password: Option<String>,
+    /// Tags, merged with any inline `#tags` found in the body
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Keep inline `#tags` in the body as links to their tag page instead of removing them
+    #[serde(default)]
+    pub tag_links: bool,
+}
+
+/// Deserializes an optional front matter date with `parse_site_date`
+fn site_date<'de, D: Deserializer<'de>>(d: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+    let Some(date) = Option::<String>::deserialize(d)? else {
+        return Ok(None);
+    };
+    parse_site_date(&date)
+        .map(Some)
+        .ok_or_else(|| de::Error::custom(format!("invalid date `{date}`")))
+}
+
+/// Deserializes an optional role name, ignoring case
+fn role_name<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Roles>, D::Error> {
+    let Some(name) = Option::<String>::deserialize(d)? else {
+        return Ok(None);
+    };
+    match name.to_lowercase().as_str() {
+        "guest" => Ok(Some(Roles::Guest)),
+        "author" => Ok(Some(Roles::Author)),
+        "admin" => Ok(Some(Roles::Admin)),
+        _ => Err(de::Error::custom(format!("unknown role `{name}`"))),
+    }
+}
+
+/// Parses a date as written in front matter. RFC 3339 dates keep their offset; dates and times
+/// without one are taken to be in the site timezone.
+/// # Returns
+/// - `Option<DateTime<Utc>>` - `None` if the date is malformed or does not exist in the site
+///   timezone
+fn parse_site_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+    if let Ok(d) = DateTime::parse_from_rfc3339(date) {
+        return Some(d.with_timezone(&Utc));
+    }
+
+    let naive = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .into_iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(date, f).ok())
+        .or_else(|| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))?;
+
+    // Ambiguous times during a DST change take the first occurrence
+    config()
+        .site
+        .timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+}
@@ -0,0 +1,24 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents the note graph: every visible post and the wikilinks between them
+#[derive(Serialize, JsonSchema)]
+pub struct Graph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Represents a post in the note graph
+#[derive(Serialize, JsonSchema)]
+pub struct GraphNode {
+    pub slug: String,
+    pub title: String,
+    pub category: String,
+}
+
+/// Represents a wikilink from one post to another, by slug
+#[derive(Serialize, JsonSchema)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+}
@@ -0,0 +1,113 @@
+use super::graph::{Graph, GraphEdge, GraphNode};
+use super::post_link::PostLink;
+use super::visibility::{listed, met_roles};
+use crate::auth;
+use crate::db::{BlogDB, map_db_err};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+use sqlx::PgConnection;
+
+/// Lists the posts that link to the given post. Links from unpublished posts are only shown to
+/// Authors and better. Posts the viewer cannot find in lists are left out.
+#[openapi]
+#[get("/blog/<slug>/backlinks")]
+pub async fn backlinks(
+    slug: String,
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<Vec<PostLink>>, Status> {
+    let viewer = user.map(|u| u.role());
+    let show_unpublished = viewer.is_some_and(|r| r.authorize(auth::Roles::Author).is_ok());
+
+    let links = sqlx::query(&format!(
+        "SELECT p.slug, p.title FROM post_link AS l \
+        JOIN post AS p ON l.source_id = p.id \
+        WHERE l.target_slug = $1 AND (p.status = 'published' OR $2) AND {} \
+        ORDER BY p.title",
+        listed("p", 3)
+    ))
+    .bind(&slug)
+    .bind(show_unpublished)
+    .bind(met_roles(viewer))
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .into_iter()
+    .map(|r| PostLink { slug: r.get("slug"), title: r.get("title") })
+    .collect();
+
+    Ok(Json(links))
+}
+
+/// Returns every post and the wikilinks between them for a graph view. Unpublished posts are
+/// only shown to Authors and better. Posts the viewer cannot find in lists are left out.
+#[openapi]
+#[get("/blog/graph")]
+pub async fn graph(
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<Graph>, Status> {
+    let viewer = user.map(|u| u.role());
+    let show_unpublished = viewer.is_some_and(|r| r.authorize(auth::Roles::Author).is_ok());
+    let roles = met_roles(viewer);
+
+    let nodes = sqlx::query(&format!(
+        "SELECT p.slug, p.title, p.category FROM post AS p \
+        WHERE (p.status = 'published' OR $1) AND {} \
+        ORDER BY p.slug",
+        listed("p", 2)
+    ))
+    .bind(show_unpublished)
+    .bind(&roles)
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .into_iter()
+    .map(|r| GraphNode { slug: r.get("slug"), title: r.get("title"), category: r.get("category") })
+    .collect();
+
+    // Only keep edges between visible posts
+    let edges = sqlx::query(&format!(
+        "SELECT s.slug AS source, t.slug AS target FROM post_link AS l \
+        JOIN post AS s ON l.source_id = s.id \
+        JOIN post AS t ON l.target_slug = t.slug \
+        WHERE (s.status = 'published' OR $1) AND (t.status = 'published' OR $1) \
+        AND {} AND {} \
+        ORDER BY s.slug, t.slug",
+        listed("s", 2),
+        listed("t", 2)
+    ))
+    .bind(show_unpublished)
+    .bind(&roles)
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .into_iter()
+    .map(|r| GraphEdge { source: r.get("source"), target: r.get("target") })
+    .collect();
+
+    Ok(Json(Graph { nodes, edges }))
+}
+
+/// Replaces the outgoing wikilinks recorded for a post
+pub(super) async fn set_links(
+    db: &mut PgConnection,
+    post_id: i32,
+    links: &[String],
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM post_link WHERE source_id = $1")
+        .bind(post_id)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    sqlx::query("INSERT INTO post_link (source_id, target_slug) SELECT $1, UNNEST($2::text[])")
+        .bind(post_id)
+        .bind(links)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use super::embed::EmbedSources;
+use super::front_matter::FrontMatter;
+use super::lint_warning::{LintKind, LintWarning};
+use super::upload::comrak_options;
+use crate::db::BlogDB;
+use chrono::Utc;
+use comrak::{Arena, nodes::{AstNode, NodeValue}, parse_document};
+use rocket_db_pools::Connection;
+
+/// Titles longer than this are cut off by search engines and social cards
+const MAX_TITLE_LENGTH: usize = 70;
+
+/// Checks a markdown body for accessibility and structure problems
+/// # Arguments
+/// - `md`: `&str` - Markdown body, without front matter or comments
+/// - `title`: `&str` - Post title
+pub(super) fn lint_markdown(md: &str, title: &str) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        warnings.push(LintWarning::new(
+            LintKind::LongTitle,
+            format!("title is longer than {MAX_TITLE_LENGTH} characters"),
+        ));
+    }
+
+    let arena = Arena::new();
+    let options = comrak_options();
+    let root = parse_document(&arena, md, &options);
+
+    let mut h1_count = 0;
+    let mut last_level = 1; // The post title is rendered as the H1
+    for node in root.descendants() {
+        match node.data.borrow().value {
+            NodeValue::Heading(ref heading) => {
+                let level = heading.level;
+                let text = text_of(node);
+                if level == 1 {
+                    h1_count += 1;
+                    if h1_count == 2 {
+                        warnings.push(LintWarning::new(
+                            LintKind::MultipleH1,
+                            format!("more than one H1 heading, second is \"{text}\""),
+                        ));
+                    }
+                }
+                if level > last_level + 1 {
+                    warnings.push(LintWarning::new(
+                        LintKind::SkippedHeadingLevel,
+                        format!("heading \"{text}\" jumps from H{last_level} to H{level}"),
+                    ));
+                }
+                last_level = level;
+            }
+            NodeValue::Image(ref image) => {
+                if text_of(node).trim().is_empty() {
+                    warnings.push(LintWarning::new(
+                        LintKind::MissingAltText,
+                        format!("image {} has no alt text", image.url),
+                    ));
+                }
+            }
+            NodeValue::Link(ref link) => {
+                if link.url.trim().is_empty() {
+                    warnings.push(LintWarning::new(
+                        LintKind::EmptyLink,
+                        format!("link \"{}\" has no target", text_of(node)),
+                    ));
+                } else if node.first_child().is_none() {
+                    warnings.push(LintWarning::new(
+                        LintKind::EmptyLink,
+                        format!("link to {} has no text", link.url),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    warnings
+}
+
+/// Warns when a post is given a `publish_date` that has already passed but is not being
+/// published or scheduled
+pub(super) fn lint_publish_date(meta: &FrontMatter, publish: bool) -> Vec<LintWarning> {
+    match meta.publish_date {
+        Some(date) if !publish && date < Utc::now() => vec![LintWarning::new(
+            LintKind::PastPublishDate,
+            format!(
+                "publish_date {} is in the past but the post is not being published",
+                date.to_rfc3339()
+            ),
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// Warns about wikilinks to posts that do not exist
+/// # Arguments
+/// - `db`: `&mut Connection<BlogDB>` - Rocket Sqlx_pools DB
+/// - `links`: `&[String]` - Linked slugs
+/// - `slug`: `&str` - Slug of the post itself, which always resolves
+pub(super) async fn lint_wikilinks(
+    db: &mut Connection<BlogDB>,
+    links: &[String],
+    slug: &str,
+) -> Result<Vec<LintWarning>, String> {
+    let found: Vec<String> = sqlx::query_scalar("SELECT slug FROM post WHERE slug = ANY($1)")
+        .bind(links)
+        .fetch_all(&mut ***db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    Ok(links
+        .iter()
+        .filter(|l| l.as_str() != slug && !found.contains(l))
+        .map(|l| LintWarning::new(
+            LintKind::UnresolvedWikilink,
+            format!("wikilink to {l} does not match any post"),
+        ))
+        .collect())
+}
+
+/// Warns about embeds that could not be inlined because the post is missing or can't be
+/// embedded, e.g. because it is unpublished
+/// # Arguments
+/// - `targets`: `&[String]` - Embedded slugs
+/// - `sources`: `&EmbedSources` - Sources loaded by `load_embeds`
+pub(super) fn lint_embeds(targets: &[String], sources: &EmbedSources) -> Vec<LintWarning> {
+    targets
+        .iter()
+        .filter(|t| !sources.contains_key(*t))
+        .map(|t| LintWarning::new(
+            LintKind::UnresolvedEmbed,
+            format!("embed of {t} does not match a published, embeddable post"),
+        ))
+        .collect()
+}
+
+/// Returns the plain text inside a node
+fn text_of<'a>(node: &'a AstNode<'a>) -> String {
+    node.descendants()
+        .filter_map(|n| match n.data.borrow().value {
+            NodeValue::Text(ref text) => Some(text.to_string()),
+            NodeValue::Code(ref code) => Some(code.literal.clone()),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,30 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Kinds of problems found when linting an upload
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    MissingAltText,
+    SkippedHeadingLevel,
+    MultipleH1,
+    EmptyLink,
+    LongTitle,
+    PastPublishDate,
+    UnresolvedWikilink,
+    UnresolvedEmbed,
+}
+
+/// Represents a problem found when linting an upload. Warnings never block an upload unless it
+/// is sent with `strict` set.
+#[derive(Serialize, JsonSchema)]
+pub struct LintWarning {
+    pub kind: LintKind,
+    pub message: String,
+}
+
+impl LintWarning {
+    pub fn new(kind: LintKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+}
@@ -0,0 +1,13 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a password-protected post that has not been unlocked yet
+#[derive(Serialize, JsonSchema)]
+pub struct LockedPost {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub excerpt: String,
+    /// Always `true`
+    pub locked: bool,
+}
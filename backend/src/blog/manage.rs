@@ -0,0 +1,210 @@
+use super::embed::{load_embeds, rerender_embedders, set_embeds};
+use super::lint::{lint_embeds, lint_wikilinks};
+use super::links::set_links;
+use super::series::assign_series;
+use super::tags::set_tags;
+use super::upload::{
+    RenderedPost, card_for, redact_password, render_post, resolve_coauthors, set_coauthors,
+};
+use super::upload_request::UploadRequest;
+use super::upload_response::UploadResponse;
+use crate::auth::{self, Roles};
+use crate::db::{BlogDB, map_db_err};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+use sqlx::Connection as _;
+
+/// Returns `Ok` if the user may change the given post. Admins may change any post, Authors only
+/// the posts they wrote. Co-authors count as owners when `allow_coauthors` is set.
+/// # Arguments
+/// - `db`: `&mut Connection<BlogDB>` - Rocket Sqlx_pools DB
+/// - `user`: `&auth::RequireRole<auth::Author>` - Rocket guard
+/// - `post_id`: `i32` - Post to check
+/// - `allow_coauthors`: `bool` - Whether co-authors count as owners
+/// # Returns
+/// - `Result<(), Status>` - `Status::NotFound` if the post does not exist, `Status::Forbidden`
+///   if the user does not own it
+pub(super) async fn authorize_owner(
+    db: &mut Connection<BlogDB>,
+    user: &auth::RequireRole<auth::Author>,
+    post_id: i32,
+    allow_coauthors: bool,
+) -> Result<(), Status> {
+    let row = sqlx::query(
+        "SELECT author_id, \
+        EXISTS (SELECT 1 FROM post_author WHERE post_id = $1 AND user_id = $2) AS coauthor \
+        FROM post WHERE id = $1",
+    )
+    .bind(post_id)
+    .bind(user.id())
+    .fetch_one(&mut ***db)
+    .await
+    .map_err(map_db_err)?;
+
+    // Admins can change anything
+    if user.role().authorize(Roles::Admin).is_ok() {
+        return Ok(());
+    }
+
+    let author_id: Option<i32> = row.get("author_id");
+    let coauthor: bool = row.get("coauthor");
+    if author_id == Some(user.id()) || (allow_coauthors && coauthor) {
+        return Ok(());
+    }
+
+    Err(Status::Forbidden)
+}
+
+/// Replaces a post with newly uploaded markdown. The original author is kept.
+#[openapi]
+#[put("/blog/post/<id>", format = "json", data = "<req>")]
+pub async fn edit_post(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+    req: Json<UploadRequest>,
+) -> Result<Json<UploadResponse>, Status> {
+    authorize_owner(&mut db, &user, id, true).await?;
+
+    let filename = req.filename.as_deref().unwrap_or("untitled.md");
+    Ok(Json(
+        update_post(&mut db, id, &req.markdown, filename, req.strict)
+            .await
+            .unwrap_or_else(UploadResponse::error),
+    ))
+}
+
+/// Purges a trashed post for good, along with its status history. Posts have to be moved to
+/// `trashed` first, so nothing is lost to a single request. Only the original author or an
+/// admin may purge a post.
+#[openapi]
+#[delete("/blog/post/<id>")]
+pub async fn delete_post(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+) -> Result<(), Status> {
+    authorize_owner(&mut db, &user, id, false).await?;
+
+    let slug: String = sqlx::query_scalar(
+        "DELETE FROM post WHERE id = $1 AND status = 'trashed' RETURNING slug",
+    )
+    .bind(id)
+    .fetch_optional(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .ok_or(Status::Conflict)?; // Not trashed
+
+    // Posts that inline this one fall back to a plain link
+    rerender_embedders(&mut **db, &slug)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(())
+}
+
+/// Works out the new password hash of an edited post. Stored sources have their password
+/// redacted, so a missing `password` keeps the current one and `password: ""` removes it.
+/// # Returns
+/// - `Option<String>` - `None` to keep the current hash, `Some("")` to remove it, otherwise the
+///   hash to store. An unchanged password keeps its hash, so readers stay unlocked.
+fn password_update(password: Option<&str>, current_hash: Option<String>) -> Option<String> {
+    match (password?, current_hash) {
+        ("", _) => Some(String::new()),
+        (password, Some(hash)) if auth::verify_password(&hash, password).is_ok() => None,
+        (password, _) => Some(auth::hash_password(password)),
+    }
+}
+
+/// Runs edited markdown through the upload pipeline and writes it over an existing post
+async fn update_post(
+    db: &mut Connection<BlogDB>,
+    id: i32,
+    md: &str,
+    filename: &str,
+    strict: bool,
+) -> Result<UploadResponse, String> {
+    let embeds = load_embeds(&mut ***db, md).await?;
+    let post = render_post(md, filename, &embeds)?;
+
+    let row = sqlx::query("SELECT author_id, password_hash, slug FROM post WHERE id = $1")
+        .bind(id)
+        .fetch_one(&mut ***db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+    // Keep the original author out of the co-author list
+    let author_id: Option<i32> = row.get("author_id");
+    let current_hash: Option<String> = row.get("password_hash");
+    let old_slug: String = row.get("slug");
+    let coauthors =
+        resolve_coauthors(db, &post.meta.coauthors, author_id.unwrap_or_default()).await?;
+    let RenderedPost { slug, title, body, meta, mut warnings } = post;
+
+    warnings.extend(lint_wikilinks(db, &body.links, &slug).await?);
+    warnings.extend(lint_embeds(&body.embeds, &embeds));
+    if strict && !warnings.is_empty() {
+        return Ok(UploadResponse::rejected(warnings));
+    }
+
+    let card = card_for(&title, &meta).await;
+    let password_hash = password_update(meta.password.as_deref(), current_hash);
+    let source = redact_password(md)?;
+
+    // Write to db, all or nothing
+    let mut tx = db.begin().await.map_err(|e| format!("database error: {e}"))?;
+    sqlx::query(
+        "UPDATE post SET slug = $1, title = $2, body = $3, source = $4, \
+        word_count = $5, reading_time = $6, excerpt = $7, \
+        description = $8, cover_image = $9, canonical_url = $10, social_card = $11, \
+        category = COALESCE($12, category), publish_date = COALESCE($13, publish_date), \
+        expires_at = $14, review_by = $15, visibility = $16, min_role = $17, \
+        password_hash = CASE WHEN $18 IS NULL THEN password_hash ELSE NULLIF($18, '') END \
+        WHERE id = $19",
+    )
+    .bind(&slug)
+    .bind(&title)
+    .bind(&body.html)
+    .bind(&source)
+    .bind(body.stats.word_count)
+    .bind(body.stats.reading_time)
+    .bind(&body.stats.excerpt)
+    .bind(&meta.description)
+    .bind(&meta.cover_image)
+    .bind(&meta.canonical_url)
+    .bind(card)
+    .bind(&meta.category)
+    .bind(meta.publish_date)
+    .bind(meta.expires_at)
+    .bind(meta.review_by)
+    .bind(meta.visibility.unwrap_or_default())
+    .bind(meta.min_role.unwrap_or(Roles::Guest))
+    .bind(password_hash)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("database error: {e}"))?;
+
+    set_coauthors(&mut tx, id, &coauthors).await?;
+    assign_series(&mut tx, id, meta.series.as_deref(), meta.series_order).await?;
+    set_links(&mut tx, id, &body.links).await?;
+    set_embeds(&mut tx, id, &body.embeds).await?;
+    set_tags(&mut tx, id, &body.tags).await?;
+
+    // Let subscribers know the post changed
+    sqlx::query("INSERT INTO updated_post (post_id) VALUES ($1)")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    // Posts that inline this one are out of date too
+    rerender_embedders(&mut tx, &slug).await?;
+    if old_slug != slug {
+        rerender_embedders(&mut tx, &old_slug).await?;
+    }
+
+    tx.commit().await.map_err(|e| format!("database error: {e}"))?;
+
+    Ok(UploadResponse::ok(id, slug, warnings))
+}
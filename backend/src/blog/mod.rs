@@ -1,3 +1,88 @@
+mod author_page;
+mod authors;
+mod calendar;
+mod embed;
+mod front_matter;
+mod graph;
+mod links;
+mod lint;
+mod lint_warning;
+mod locked_post;
+mod manage;
+mod obsidian;
+mod open_graph;
+mod post;
+mod post_link;
+mod post_status;
+mod post_summary;
+mod post_view;
+mod post_visibility;
+mod posts;
+mod preview;
+mod preview_link;
+mod preview_request;
+mod preview_token;
+mod protected;
+mod publish_calendar;
+mod publish_event;
+mod publish_queue;
+mod publish_queue_request;
+mod queue;
+mod queue_order_request;
+mod queued_post;
+mod scheduler;
+mod series;
+mod series_index;
+mod series_nav;
+mod social_card;
+mod stats;
+mod status;
+mod status_change;
+mod status_request;
+mod tags;
+mod unlock_request;
 mod upload;
+mod upload_request;
+mod upload_response;
+mod visibility;
 
+pub use author_page::AuthorPage;
+pub use authors::*;
+pub use calendar::*;
+pub use graph::{Graph, GraphEdge, GraphNode};
+pub use links::*;
+pub use lint_warning::{LintKind, LintWarning};
+pub use locked_post::LockedPost;
+pub use manage::*;
+pub use open_graph::OpenGraph;
+pub use post::Post;
+pub use post_link::PostLink;
+pub use post_status::PostStatus;
+pub use post_visibility::PostVisibility;
+pub use post_summary::PostSummary;
+pub use post_view::PostView;
+pub use posts::*;
+pub use preview::*;
+pub use preview_link::PreviewLink;
+pub use preview_request::PreviewRequest;
+pub use preview_token::PreviewToken;
+pub use protected::*;
+pub use publish_calendar::{CalendarEntry, CalendarGap, PublishCalendar};
+pub use publish_event::{PublishEvent, PublishEvents, PublishSource};
+pub use publish_queue::PublishQueue;
+pub use publish_queue_request::PublishQueueRequest;
+pub use queue::*;
+pub use queue_order_request::QueueOrderRequest;
+pub use queued_post::QueuedPost;
+pub use scheduler::scheduler;
+pub use series::*;
+pub use series_index::SeriesIndex;
+pub use series_nav::SeriesNav;
+pub use status::*;
+pub use status_change::StatusChange;
+pub use status_request::StatusRequest;
+pub use tags::*;
+pub use unlock_request::UnlockRequest;
 pub use upload::*;
+pub use upload_request::UploadRequest;
+pub use upload_response::UploadResponse;
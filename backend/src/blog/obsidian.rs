@@ -0,0 +1,156 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Matches `%%private comments%%`, which may span lines
+static COMMENT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)%%.*?%%").unwrap());
+
+/// Matches `==highlighted text==`
+static HIGHLIGHT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"==([^=\n]+?)==").unwrap());
+
+/// Private use characters `highlight` leaves in place of `<mark>` tags. Raw HTML in markdown is
+/// escaped, so `restore_highlights` swaps them for real tags once the HTML is rendered.
+const MARK_OPEN: &str = "\u{E000}";
+const MARK_CLOSE: &str = "\u{E001}";
+
+/// Matches inline `#tags` at the start of a line or after whitespace. Headings are left alone
+/// since they need a space after the `#`.
+static TAG_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(^|[ \t])#([A-Za-z_][\w/-]*)").unwrap());
+
+/// Removes Obsidian `%%comments%%`. Applied everywhere, code blocks included, so private notes
+/// can never leak into published HTML.
+pub(super) fn strip_comments(md: &str) -> String {
+    COMMENT_RE.replace_all(md, "").into_owned()
+}
+
+/// Marks `==highlights==` with placeholders, outside of code blocks and code spans
+pub(super) fn highlight(md: &str) -> String {
+    // Placeholders typed by the author would turn into tags too
+    let md = md.replace([MARK_OPEN, MARK_CLOSE], "");
+    let replacement = format!("{MARK_OPEN}$1{MARK_CLOSE}");
+    map_prose(&md, |line| HIGHLIGHT_RE.replace_all(line, replacement.as_str()).into_owned())
+}
+
+/// Turns the placeholders left by `highlight` into `<mark>` elements
+pub(super) fn restore_highlights(html: &str) -> String {
+    html.replace(MARK_OPEN, "<mark>").replace(MARK_CLOSE, "</mark>")
+}
+
+/// Pulls inline `#tags` out of the body, outside of code blocks and code spans
+/// # Arguments
+/// - `md`: `&str` - Markdown body
+/// - `tag_links`: `bool` - Replace tags with links to their tag page instead of removing them
+/// # Returns
+/// - `(String, Vec<String>)` - Rewritten markdown and the tags found, without the `#`
+pub(super) fn extract_tags(md: &str, tag_links: bool) -> (String, Vec<String>) {
+    let mut tags: Vec<String> = Vec::new();
+    let body = map_prose(md, |line| {
+        TAG_RE.replace_all(line, |caps: &regex::Captures| {
+            let lead = &caps[1];
+            let tag = &caps[2];
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+            if tag_links {
+                format!("{lead}[#{tag}](/tag/{})", slug::slugify(tag))
+            } else {
+                lead.to_string()
+            }
+        }).into_owned()
+    });
+    (body, tags)
+}
+
+/// Applies `f` to every line outside of fenced and indented code blocks. Code spans are masked
+/// while `f` runs, so it never sees their content.
+fn map_prose(md: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(md.len());
+    let mut fence: Option<&str> = None;
+    // Indented code can't interrupt a paragraph, so it only starts after a blank line
+    let mut after_blank = true;
+    let mut indented = false;
+
+    for line in md.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let blank = trimmed.is_empty();
+        let marker = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m));
+        match (fence, marker) {
+            // Closing fence
+            (Some(open), Some(m)) if open == m => {
+                fence = None;
+                out.push_str(line);
+            }
+            // Inside a code block
+            (Some(_), _) => out.push_str(line),
+            // Opening fence
+            (None, Some(m)) => {
+                fence = Some(m);
+                out.push_str(line);
+            }
+            (None, None) => {
+                indented = !blank
+                    && (line.starts_with("    ") || line.starts_with('\t'))
+                    && (after_blank || indented);
+                if indented || blank {
+                    out.push_str(line);
+                } else {
+                    let (masked, spans) = mask_code_spans(line);
+                    out.push_str(&unmask_code_spans(&f(&masked), &spans));
+                }
+            }
+        }
+        after_blank = blank;
+    }
+
+    out
+}
+
+/// Replaces each `` `code span` `` in a line with a placeholder
+/// # Returns
+/// - `(String, Vec<&str>)` - Masked line and the code spans, in order
+fn mask_code_spans(line: &str) -> (String, Vec<&str>) {
+    let mut masked = String::with_capacity(line.len());
+    let mut spans = Vec::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find('`') {
+        let ticks = rest[start..].len() - rest[start..].trim_start_matches('`').len();
+        let after = start + ticks;
+        // A span closes on a run of exactly as many backticks
+        let close = rest[after..].match_indices(&rest[start..after]).find(|(i, _)| {
+            let end = after + i + ticks;
+            !rest[end..].starts_with('`') && !rest[..after + i].ends_with('`')
+        });
+        match close {
+            Some((i, _)) => {
+                let end = after + i + ticks;
+                masked.push_str(&rest[..start]);
+                masked.push_str(&placeholder(spans.len()));
+                spans.push(&rest[start..end]);
+                rest = &rest[end..];
+            }
+            // Unmatched backticks are plain text
+            None => {
+                masked.push_str(&rest[..after]);
+                rest = &rest[after..];
+            }
+        }
+    }
+    masked.push_str(rest);
+
+    (masked, spans)
+}
+
+/// Puts the code spans taken out by `mask_code_spans` back
+fn unmask_code_spans(line: &str, spans: &[&str]) -> String {
+    let mut line = line.to_string();
+    for (i, span) in spans.iter().enumerate() {
+        line = line.replacen(&placeholder(i), span, 1);
+    }
+    line
+}
+
+/// Private use characters, which no markdown rule or Obsidian pattern matches
+fn placeholder(i: usize) -> String {
+    format!("\u{E000}{i}\u{E001}")
+}
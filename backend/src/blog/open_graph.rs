@@ -0,0 +1,18 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents the Open Graph and Twitter card metadata of a post
+#[derive(Serialize, JsonSchema)]
+pub struct OpenGraph {
+    pub title: String,
+    pub description: String,
+    /// Absolute URL of the cover image or generated social card
+    pub image: Option<String>,
+    /// Canonical absolute URL of the post
+    pub url: String,
+    pub site_name: String,
+    /// Open Graph `og:type`
+    pub og_type: String,
+    /// Twitter `twitter:card`
+    pub twitter_card: String,
+}
@@ -0,0 +1,29 @@
+use super::post_visibility::PostVisibility;
+use super::series_nav::SeriesNav;
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a published post
+#[derive(Serialize, JsonSchema)]
+pub struct Post {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    /// Rendered HTML
+    pub body: String,
+    pub category: String,
+    pub visibility: PostVisibility,
+    pub publish_date: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub review_by: Option<DateTime<Utc>>,
+    /// Set once `review_by` has passed, so readers can be warned. Posts past `expires_at` are
+    /// archived instead.
+    pub outdated: bool,
+    pub word_count: i32,
+    /// Estimated reading time in minutes
+    pub reading_time: i32,
+    /// Usernames of the author followed by any co-authors
+    pub authors: Vec<String>,
+    pub series: Option<SeriesNav>,
+}
@@ -0,0 +1,9 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a link to another post
+#[derive(Serialize, JsonSchema)]
+pub struct PostLink {
+    pub slug: String,
+    pub title: String,
+}
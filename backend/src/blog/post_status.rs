@@ -0,0 +1,47 @@
+use rocket::http::Status;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+/// Publishing status of a post
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema,
+    FromFormField,
+)]
+#[sqlx(type_name = "post_status")] // Must match Postgres enum name
+#[sqlx(rename_all = "lowercase")] // Must match Postgres variant case
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    /// Not visible and not going anywhere
+    #[default]
+    Draft,
+    /// Waiting in the publish queue
+    Queued,
+    /// Published by the scheduler once its `publish_date` passes
+    Scheduled,
+    Published,
+    /// Taken down but kept
+    Archived,
+    /// Deleted, but can still be restored as a draft
+    Trashed,
+}
+
+impl PostStatus {
+    /// Returns `Ok` if a post may move from this status to `to`
+    /// # Returns
+    /// - `Result<(), Status>` - `Status::Conflict` if the transition is not allowed
+    pub fn transition(self, to: Self) -> Result<(), Status> {
+        use PostStatus::*;
+        let allowed = match self {
+            Draft => matches!(to, Queued | Scheduled | Published | Trashed),
+            Queued => matches!(to, Draft | Scheduled | Published | Trashed),
+            Scheduled => matches!(to, Draft | Queued | Published | Trashed),
+            Published => matches!(to, Draft | Archived | Trashed),
+            Archived => matches!(to, Draft | Published | Trashed),
+            Trashed => matches!(to, Draft),
+        };
+        if !allowed {
+            return Err(Status::Conflict);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use rocket_db_pools::sqlx::{Row, postgres::PgRow};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a post in a listing, without its body
+#[derive(Serialize, JsonSchema)]
+pub struct PostSummary {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub category: String,
+    pub publish_date: Option<DateTime<Utc>>,
+    pub word_count: i32,
+    /// Estimated reading time in minutes
+    pub reading_time: i32,
+    /// Front matter `summary`, or the start of the post cut at a sentence boundary
+    pub excerpt: String,
+}
+
+/// Columns read by `PostSummary::from_row`, for use in `SELECT` lists
+pub(super) const POST_SUMMARY_COLUMNS: &str =
+    "p.id, p.slug, p.title, p.category, p.publish_date, p.word_count, p.reading_time, p.excerpt";
+
+impl PostSummary {
+    /// Reads a summary from a row selected with `POST_SUMMARY_COLUMNS`
+    pub(super) fn from_row(r: &PgRow) -> Self {
+        Self {
+            id: r.get("id"),
+            slug: r.get("slug"),
+            title: r.get("title"),
+            category: r.get("category"),
+            publish_date: r.get("publish_date"),
+            word_count: r.get("word_count"),
+            reading_time: r.get("reading_time"),
+            excerpt: r.get("excerpt"),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use super::locked_post::LockedPost;
+use super::post::Post;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a post as the reader may see it
+#[derive(Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PostView {
+    Post(Post),
+    /// Password-protected post; unlock it with `/blog/<slug>/unlock`
+    Locked(LockedPost),
+}
@@ -0,0 +1,17 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+/// Who can find and read a published post
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema)]
+#[sqlx(type_name = "post_visibility")] // Must match Postgres enum name
+#[sqlx(rename_all = "lowercase")] // Must match Postgres variant case
+#[serde(rename_all = "lowercase")]
+pub enum PostVisibility {
+    /// Listed everywhere
+    #[default]
+    Public,
+    /// Readable by direct link, but left out of every list
+    Unlisted,
+    /// Only for signed-in users with the post's minimum role
+    Members,
+}
@@ -0,0 +1,171 @@
+use super::locked_post::LockedPost;
+use super::open_graph::OpenGraph;
+use super::post::Post;
+use super::post_view::PostView;
+use super::post_visibility::PostVisibility;
+use super::protected::is_unlocked;
+use super::series::series_nav;
+use super::visibility::authorize_read;
+use crate::auth::{self, Roles};
+use crate::config::config;
+use crate::db::{BlogDB, map_db_err};
+use rocket::{
+    http::{CookieJar, Status},
+    serde::json::Json,
+};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+
+/// Returns a published post by its slug. Members-only posts need a signed-in user with the
+/// post's minimum role. Password-protected posts only show their title and excerpt until they
+/// are unlocked.
+#[openapi]
+#[get("/blog/<slug>")]
+pub async fn read_post(
+    slug: String,
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+    jar: &CookieJar<'_>,
+) -> Result<Json<PostView>, Status> {
+    let viewer = user.map(|u| u.role());
+    let id = readable_post_id(&mut db, &slug, viewer).await?;
+
+    let row = sqlx::query("SELECT title, excerpt, password_hash FROM post WHERE id = $1")
+        .bind(id)
+        .fetch_one(&mut **db)
+        .await
+        .map_err(map_db_err)?;
+    if let Some(hash) = row.get::<Option<String>, _>("password_hash") {
+        if !is_unlocked(jar, id, &hash) {
+            return Ok(Json(PostView::Locked(LockedPost {
+                id,
+                slug,
+                title: row.get("title"),
+                excerpt: row.get("excerpt"),
+                locked: true,
+            })));
+        }
+    }
+
+    load_post(&mut db, id, viewer).await.map(|p| Json(PostView::Post(p)))
+}
+
+/// Returns the ID of a published post the viewer may read by direct link
+pub(crate) async fn readable_post_id(
+    db: &mut Connection<BlogDB>,
+    slug: &str,
+    viewer: Option<Roles>,
+) -> Result<i32, Status> {
+    let row = sqlx::query(
+        "SELECT id, visibility, min_role FROM post WHERE slug = $1 AND status = 'published'",
+    )
+    .bind(slug)
+    .fetch_one(&mut ***db)
+    .await
+    .map_err(map_db_err)?;
+    let visibility: PostVisibility = row.get("visibility");
+    authorize_read(visibility, row.get("min_role"), viewer)?;
+
+    Ok(row.get("id"))
+}
+
+/// Loads a post with its byline and series navigation, whatever its status or visibility
+/// # Arguments
+/// - `db`: `&mut Connection<BlogDB>` - Rocket Sqlx_pools DB
+/// - `id`: `i32` - Post to load
+/// - `viewer`: `Option<Roles>` - Role of the signed-in user, for the series navigation
+pub(super) async fn load_post(
+    db: &mut Connection<BlogDB>,
+    id: i32,
+    viewer: Option<Roles>,
+) -> Result<Post, Status> {
+    let row = sqlx::query(
+        "SELECT p.slug, p.title, p.body, p.category, p.visibility, p.publish_date, \
+        p.expires_at, p.review_by, \
+        COALESCE(p.review_by <= NOW(), false) AS outdated, \
+        p.word_count, p.reading_time, u.username AS author \
+        FROM post AS p \
+        LEFT JOIN users AS u ON p.author_id = u.id \
+        WHERE p.id = $1",
+    )
+    .bind(id)
+    .fetch_one(&mut ***db)
+    .await
+    .map_err(map_db_err)?;
+
+    // Byline
+    let mut authors: Vec<String> = row.get::<Option<String>, _>("author").into_iter().collect();
+    let coauthors: Vec<String> = sqlx::query_scalar(
+        "SELECT u.username FROM post_author AS a \
+        JOIN users AS u ON a.user_id = u.id \
+        WHERE a.post_id = $1 \
+        ORDER BY u.username",
+    )
+    .bind(id)
+    .fetch_all(&mut ***db)
+    .await
+    .map_err(map_db_err)?;
+    authors.extend(coauthors);
+
+    let series = series_nav(db, id, viewer).await?;
+
+    Ok(Post {
+        id,
+        slug: row.get("slug"),
+        title: row.get("title"),
+        body: row.get("body"),
+        category: row.get("category"),
+        visibility: row.get("visibility"),
+        publish_date: row.get("publish_date"),
+        expires_at: row.get("expires_at"),
+        review_by: row.get("review_by"),
+        outdated: row.get("outdated"),
+        word_count: row.get("word_count"),
+        reading_time: row.get("reading_time"),
+        authors,
+        series,
+    })
+}
+
+/// Returns the Open Graph and Twitter card metadata of a published post
+#[openapi]
+#[get("/blog/<slug>/meta")]
+pub async fn post_meta(
+    slug: String,
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<OpenGraph>, Status> {
+    let id = readable_post_id(&mut db, &slug, user.map(|u| u.role())).await?;
+    let row = sqlx::query(
+        "SELECT title, COALESCE(description, excerpt) AS description, \
+        COALESCE(cover_image, social_card) AS image, canonical_url \
+        FROM post WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_one(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+
+    let site = config().site;
+    let image: Option<String> = row.get("image");
+    let canonical: Option<String> = row.get("canonical_url");
+
+    Ok(Json(OpenGraph {
+        title: row.get("title"),
+        description: row.get("description"),
+        image: image.map(|i| absolute_url(&site.url, &i)),
+        url: canonical.unwrap_or_else(|| format!("{}/blog/{slug}", site.url)),
+        site_name: site.name,
+        og_type: "article".into(),
+        twitter_card: "summary_large_image".into(),
+    }))
+}
+
+/// Prefixes site-relative URLs with the site URL
+fn absolute_url(base: &str, url: &str) -> String {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        url.to_string()
+    } else {
+        format!("{base}/{}", url.trim_start_matches('/'))
+    }
+}
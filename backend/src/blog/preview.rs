@@ -0,0 +1,154 @@
+use super::manage::authorize_owner;
+use super::post::Post;
+use super::posts::load_post;
+use super::preview_link::PreviewLink;
+use super::preview_request::PreviewRequest;
+use super::preview_token::PreviewToken;
+use crate::auth;
+use crate::config::config;
+use crate::db::{BlogDB, map_db_err};
+use chrono::{DateTime, Utc};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+
+/// Lifetime of a preview link when none is requested, in hours
+const DEFAULT_PREVIEW_HOURS: i64 = 72;
+/// Longest a preview link may last, in hours
+const MAX_PREVIEW_HOURS: i64 = 720;
+
+/// Creates a signed, time-limited link to an unpublished post for reviewers without an account
+#[openapi]
+#[post("/blog/post/<id>/previews", format = "json", data = "<req>")]
+pub async fn create_preview(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+    req: Json<PreviewRequest>,
+) -> Result<Json<PreviewLink>, Status> {
+    authorize_owner(&mut db, &user, id, true).await?;
+
+    let hours = req.expires_in.unwrap_or(DEFAULT_PREVIEW_HOURS);
+    if !(1..=MAX_PREVIEW_HOURS).contains(&hours) {
+        return Err(Status::BadRequest);
+    }
+
+    // Published posts need no preview
+    let row = sqlx::query(
+        "INSERT INTO preview_token (post_id, label, created_by, expires_at) \
+        SELECT id, $2, $3, NOW() + make_interval(hours => $4) \
+        FROM post WHERE id = $1 AND status <> 'published' \
+        RETURNING id, expires_at",
+    )
+    .bind(id)
+    .bind(&req.label)
+    .bind(user.id())
+    .bind(hours as i32)
+    .fetch_optional(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .ok_or(Status::Conflict)?;
+    let token_id: i32 = row.get("id");
+    let expires_at: DateTime<Utc> = row.get("expires_at");
+
+    let token = auth::create_preview_jwt(id, token_id, expires_at)
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(PreviewLink {
+        id: token_id,
+        label: req.label.clone(),
+        url: format!("{}/preview/{token}", config().site.url),
+        token,
+        expires_at,
+    }))
+}
+
+/// Lists the preview links issued for a post, newest first
+#[openapi]
+#[get("/blog/post/<id>/previews")]
+pub async fn list_previews(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+) -> Result<Json<Vec<PreviewToken>>, Status> {
+    authorize_owner(&mut db, &user, id, true).await?;
+
+    let previews = sqlx::query(
+        "SELECT t.id, t.label, u.username, t.created_at, t.expires_at, t.revoked_at \
+        FROM preview_token AS t \
+        LEFT JOIN users AS u ON u.id = t.created_by \
+        WHERE t.post_id = $1 \
+        ORDER BY t.created_at DESC",
+    )
+    .bind(id)
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .into_iter()
+    .map(|r| PreviewToken {
+        id: r.get("id"),
+        label: r.get("label"),
+        created_by: r.get("username"),
+        created_at: r.get("created_at"),
+        expires_at: r.get("expires_at"),
+        revoked_at: r.get("revoked_at"),
+    })
+    .collect();
+
+    Ok(Json(previews))
+}
+
+/// Revokes a preview link
+#[openapi]
+#[delete("/blog/post/<id>/previews/<preview_id>")]
+pub async fn revoke_preview(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+    preview_id: i32,
+) -> Result<(), Status> {
+    authorize_owner(&mut db, &user, id, true).await?;
+
+    let revoked = sqlx::query(
+        "UPDATE preview_token SET revoked_at = COALESCE(revoked_at, NOW()) \
+        WHERE id = $1 AND post_id = $2",
+    )
+    .bind(preview_id)
+    .bind(id)
+    .execute(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+    if revoked.rows_affected() == 0 {
+        return Err(Status::NotFound);
+    }
+
+    Ok(())
+}
+
+/// Returns an unpublished post to anyone holding a valid preview token
+#[openapi]
+#[get("/preview/<token>")]
+pub async fn read_preview(
+    token: &str,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<Post>, Status> {
+    let claims = auth::get_preview_claims(token).map_err(|_| Status::Unauthorized)?;
+    let (Some(post_id), Some(token_id)) = (claims.post_id(), claims.token_id()) else {
+        return Err(Status::Unauthorized);
+    };
+
+    let valid: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM preview_token \
+        WHERE id = $1 AND post_id = $2 AND revoked_at IS NULL AND expires_at > NOW())",
+    )
+    .bind(token_id)
+    .bind(post_id)
+    .fetch_one(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+    if !valid {
+        return Err(Status::Unauthorized);
+    }
+
+    load_post(&mut db, post_id, None).await.map(Json)
+}
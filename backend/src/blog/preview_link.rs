@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a newly created draft preview link. The token is only ever shown here.
+#[derive(Serialize, JsonSchema)]
+pub struct PreviewLink {
+    pub id: i32,
+    pub label: Option<String>,
+    pub token: String,
+    /// Public URL of the preview
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,11 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Deserialize;
+
+/// Represents a request for a draft preview link
+#[derive(Deserialize, JsonSchema)]
+pub struct PreviewRequest {
+    /// Note on who the link is for
+    pub label: Option<String>,
+    /// Hours until the link expires. Defaults to 72, at most 720.
+    pub expires_in: Option<i64>,
+}
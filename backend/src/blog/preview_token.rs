@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a draft preview link issued for a post
+#[derive(Serialize, JsonSchema)]
+pub struct PreviewToken {
+    pub id: i32,
+    pub label: Option<String>,
+    /// Username of the user who created the link
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,99 @@
+use super::post::Post;
+use super::posts::{load_post, readable_post_id};
+use super::unlock_request::UnlockRequest;
+use crate::auth;
+use crate::db::{BlogDB, map_db_err};
+use chrono::{Duration, Utc};
+use rocket::{
+    http::{Cookie, CookieJar, SameSite, Status},
+    serde::json::Json,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::openapi;
+use sha2::{Digest, Sha256};
+
+/// How long an unlocked post stays unlocked
+const UNLOCK_DAYS: i64 = 7;
+
+/// Unlocks a password-protected post. Sets a cookie named after the post's id, so later reads
+/// and its comments return everything, even after the slug changes.
+#[openapi]
+#[post("/blog/<slug>/unlock", format = "json", data = "<req>")]
+pub async fn unlock(
+    slug: String,
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+    jar: &CookieJar<'_>,
+    req: Json<UnlockRequest>,
+) -> Result<Json<Post>, Status> {
+    let viewer = user.map(|u| u.role());
+    let id = readable_post_id(&mut db, &slug, viewer).await?;
+
+    let hash: Option<String> = sqlx::query_scalar("SELECT password_hash FROM post WHERE id = $1")
+        .bind(id)
+        .fetch_one(&mut **db)
+        .await
+        .map_err(map_db_err)?;
+    if let Some(hash) = hash {
+        auth::verify_password(&hash, &req.password)?;
+
+        let expiration = Utc::now() + Duration::days(UNLOCK_DAYS);
+        let token = auth::create_unlock_jwt(id, &fingerprint(&hash), expiration)
+            .map_err(|_| Status::InternalServerError)?;
+        let mut cookie = Cookie::new(cookie_name(id), token);
+        cookie.set_http_only(true);
+        cookie.set_secure(true);
+        cookie.set_same_site(SameSite::Strict);
+        cookie.set_path("/");
+        cookie.set_max_age(rocket::time::Duration::days(UNLOCK_DAYS));
+        jar.add(cookie);
+    }
+
+    load_post(&mut db, id, viewer).await.map(Json)
+}
+
+/// Returns `true` if the request carries a valid unlock cookie for the post
+/// # Arguments
+/// - `jar`: `&CookieJar` - Request cookies
+/// - `post_id`: `i32` - Protected post
+/// - `hash`: `&str` - The post's current password hash
+pub(super) fn is_unlocked(jar: &CookieJar<'_>, post_id: i32, hash: &str) -> bool {
+    let Some(cookie) = jar.get(&cookie_name(post_id)) else {
+        return false;
+    };
+    match auth::get_unlock_claims(cookie.value()) {
+        Ok(claims) => claims.post_id() == Some(post_id) && claims.key == fingerprint(hash),
+        Err(_) => false,
+    }
+}
+
+/// Returns `Status::Forbidden` if the post is password-protected and the request hasn't
+/// unlocked it
+/// # Arguments
+/// - `db`: `&mut Connection<BlogDB>` - Rocket Sqlx_pools DB
+/// - `jar`: `&CookieJar` - Request cookies
+/// - `post_id`: `i32` - Post being accessed
+pub(crate) async fn require_unlocked(
+    db: &mut Connection<BlogDB>,
+    jar: &CookieJar<'_>,
+    post_id: i32,
+) -> Result<(), Status> {
+    let hash: Option<String> = sqlx::query_scalar("SELECT password_hash FROM post WHERE id = $1")
+        .bind(post_id)
+        .fetch_one(&mut ***db)
+        .await
+        .map_err(map_db_err)?;
+    match hash {
+        Some(hash) if !is_unlocked(jar, post_id, &hash) => Err(Status::Forbidden),
+        _ => Ok(()),
+    }
+}
+
+fn cookie_name(post_id: i32) -> String {
+    format!("unlock-{post_id}")
+}
+
+/// Short digest of a password hash. Changes whenever the password does.
+fn fingerprint(hash: &str) -> String {
+    format!("{:x}", Sha256::digest(hash.as_bytes()))[..16].to_string()
+}
@@ -0,0 +1,37 @@
+use super::publish_event::PublishSource;
+use chrono::{DateTime, NaiveDate, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents the projected publish dates of pending posts over a date range
+#[derive(Serialize, JsonSchema)]
+pub struct PublishCalendar {
+    /// First day, in the site timezone
+    pub from: NaiveDate,
+    /// Last day, inclusive
+    pub to: NaiveDate,
+    /// Sorted by publish date
+    pub entries: Vec<CalendarEntry>,
+    /// Queue runs that will find nothing to publish
+    pub gaps: Vec<CalendarGap>,
+}
+
+/// Represents a pending post and when it is expected to go out
+#[derive(Serialize, JsonSchema)]
+pub struct CalendarEntry {
+    pub post_id: i32,
+    pub slug: String,
+    pub title: String,
+    pub publish_date: DateTime<Utc>,
+    /// `scheduled` for scheduled posts, `queue` for queued posts
+    pub source: PublishSource,
+    /// Queue expected to publish the post
+    pub queue_id: Option<i32>,
+}
+
+/// Represents a queue run with no queued post left to publish
+#[derive(Serialize, JsonSchema)]
+pub struct CalendarGap {
+    pub queue_id: i32,
+    pub run_at: DateTime<Utc>,
+}
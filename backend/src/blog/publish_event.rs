@@ -0,0 +1,44 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// What caused a post to be published
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishSource {
+    /// The post's `publish_date` passed
+    Scheduled,
+    /// A publish queue ticked
+    Queue,
+    /// An admin published the next queued post by hand
+    Manual,
+}
+
+/// Sent when the scheduler publishes a post
+#[derive(Clone, Debug)]
+pub struct PublishEvent {
+    pub post_id: i32,
+    pub slug: String,
+    pub source: PublishSource,
+}
+
+/// Broadcasts `PublishEvent`s. Managed by Rocket; subscribe from a fairing or request guard
+/// with `State<PublishEvents>`.
+#[derive(Clone)]
+pub struct PublishEvents(broadcast::Sender<PublishEvent>);
+
+impl PublishEvents {
+    pub fn new() -> Self {
+        Self(broadcast::channel(64).0)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PublishEvent> {
+        self.0.subscribe()
+    }
+
+    pub(super) fn send(&self, event: PublishEvent) {
+        println!("Published post {} ({}) from {:?}", event.post_id, event.slug, event.source);
+        // No subscribers is fine
+        let _ = self.0.send(event);
+    }
+}
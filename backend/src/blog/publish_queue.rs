@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a publish queue schedule. Each time it runs, the next queued post matching its
+/// category and tag is published.
+#[derive(Serialize, JsonSchema)]
+pub struct PublishQueue {
+    pub id: i32,
+    pub next_run_at: DateTime<Utc>,
+    /// Time between runs in seconds
+    pub run_interval: i64,
+    pub active: bool,
+    /// Only publish posts in this category
+    pub category: Option<String>,
+    /// Only publish posts with this tag, as a slug
+    pub tag: Option<String>,
+}
@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Deserialize;
+
+/// Represents a request to create or edit a publish queue. Missing fields are left unchanged,
+/// or given their defaults on create.
+#[derive(Deserialize, JsonSchema)]
+pub struct PublishQueueRequest {
+    /// Defaults to now
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// Time between runs in seconds, at least 1. Defaults to one week.
+    pub run_interval: Option<i64>,
+    /// Defaults to `true`
+    pub active: Option<bool>,
+    /// Only publish posts in this category. An empty string removes the restriction.
+    pub category: Option<String>,
+    /// Only publish posts with this tag. An empty string removes the restriction.
+    pub tag: Option<String>,
+}
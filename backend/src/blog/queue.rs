@@ -0,0 +1,272 @@
+use super::embed::rerender_embedders;
+use super::post_link::PostLink;
+use super::publish_event::{PublishEvent, PublishEvents, PublishSource};
+use super::publish_queue::PublishQueue;
+use super::publish_queue_request::PublishQueueRequest;
+use super::queue_order_request::QueueOrderRequest;
+use super::queued_post::QueuedPost;
+use crate::auth;
+use crate::db::{BlogDB, map_db_err};
+use rocket::{State, http::Status, serde::json::Json};
+use rocket_db_pools::{Connection, sqlx::{Row, postgres::PgRow}};
+use rocket_okapi::openapi;
+use sqlx::Connection as _;
+use std::collections::HashSet;
+
+/// Columns read by `queue_from_row`
+const QUEUE_COLUMNS: &str = "id, next_run_at, \
+    EXTRACT(EPOCH FROM run_interval)::bigint AS run_interval, active, category, tag_slug";
+
+fn queue_from_row(r: &PgRow) -> PublishQueue {
+    PublishQueue {
+        id: r.get("id"),
+        next_run_at: r.get("next_run_at"),
+        run_interval: r.get("run_interval"),
+        active: r.get("active"),
+        category: r.get("category"),
+        tag: r.get("tag_slug"),
+    }
+}
+
+/// Lists every publish queue schedule
+#[openapi]
+#[get("/admin/queues")]
+pub async fn list_queues(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<Vec<PublishQueue>>, Status> {
+    let queues = sqlx::query(&format!(
+        "SELECT {QUEUE_COLUMNS} FROM publish_queue ORDER BY next_run_at"
+    ))
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .iter()
+    .map(queue_from_row)
+    .collect();
+
+    Ok(Json(queues))
+}
+
+/// Creates a publish queue schedule
+#[openapi]
+#[post("/admin/queues", format = "json", data = "<req>")]
+pub async fn create_queue(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    req: Json<PublishQueueRequest>,
+) -> Result<Json<PublishQueue>, Status> {
+    validate_interval(&req)?;
+    let row = sqlx::query(&format!(
+        "INSERT INTO publish_queue (next_run_at, run_interval, active, category, tag_slug) \
+        VALUES (COALESCE($1, NOW()), COALESCE(make_interval(secs => $2), interval '7 days'), \
+        COALESCE($3, true), NULLIF($4, ''), NULLIF($5, '')) \
+        RETURNING {QUEUE_COLUMNS}"
+    ))
+    .bind(req.next_run_at)
+    .bind(req.run_interval.map(|s| s as f64))
+    .bind(req.active)
+    .bind(&req.category)
+    .bind(req.tag.as_deref().map(slug::slugify))
+    .fetch_one(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(Json(queue_from_row(&row)))
+}
+
+/// Edits a publish queue schedule
+#[openapi]
+#[patch("/admin/queues/<id>", format = "json", data = "<req>")]
+pub async fn edit_queue(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+    req: Json<PublishQueueRequest>,
+) -> Result<Json<PublishQueue>, Status> {
+    validate_interval(&req)?;
+    let row = sqlx::query(&format!(
+        "UPDATE publish_queue SET \
+        next_run_at = COALESCE($1, next_run_at), \
+        run_interval = COALESCE(make_interval(secs => $2), run_interval), \
+        active = COALESCE($3, active), \
+        category = NULLIF(COALESCE($4, category), ''), \
+        tag_slug = NULLIF(COALESCE($5, tag_slug), '') \
+        WHERE id = $6 \
+        RETURNING {QUEUE_COLUMNS}"
+    ))
+    .bind(req.next_run_at)
+    .bind(req.run_interval.map(|s| s as f64))
+    .bind(req.active)
+    .bind(&req.category)
+    .bind(req.tag.as_deref().map(slug::slugify))
+    .bind(id)
+    .fetch_one(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(Json(queue_from_row(&row)))
+}
+
+/// Pauses a publish queue schedule
+#[openapi]
+#[post("/admin/queues/<id>/pause")]
+pub async fn pause_queue(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+) -> Result<Json<PublishQueue>, Status> {
+    set_queue_active(&mut db, id, false).await.map(Json)
+}
+
+/// Resumes a paused publish queue schedule
+#[openapi]
+#[post("/admin/queues/<id>/resume")]
+pub async fn resume_queue(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+) -> Result<Json<PublishQueue>, Status> {
+    set_queue_active(&mut db, id, true).await.map(Json)
+}
+
+/// Refuses run intervals that would leave a queue due forever and publish on every tick
+fn validate_interval(req: &PublishQueueRequest) -> Result<(), Status> {
+    match req.run_interval {
+        Some(secs) if secs <= 0 => Err(Status::BadRequest),
+        _ => Ok(()),
+    }
+}
+
+async fn set_queue_active(
+    db: &mut Connection<BlogDB>,
+    id: i32,
+    active: bool,
+) -> Result<PublishQueue, Status> {
+    // A resumed queue picks up at its next slot instead of catching up on the runs it missed
+    let row = sqlx::query(&format!(
+        "UPDATE publish_queue SET active = $1, \
+        next_run_at = CASE WHEN $1 THEN next_queue_run(next_run_at, run_interval) \
+            ELSE next_run_at END \
+        WHERE id = $2 RETURNING {QUEUE_COLUMNS}"
+    ))
+    .bind(active)
+    .bind(id)
+    .fetch_one(&mut ***db)
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(queue_from_row(&row))
+}
+
+/// Lists queued posts in the order they will be published
+#[openapi]
+#[get("/admin/queue/posts")]
+pub async fn queued_posts(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<Vec<QueuedPost>>, Status> {
+    let posts = sqlx::query(
+        "SELECT id, slug, title, category, queue_position, upload_date \
+        FROM post \
+        WHERE status = 'queued' \
+        ORDER BY queue_position NULLS LAST, upload_date",
+    )
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .into_iter()
+    .map(|r| QueuedPost {
+        id: r.get("id"),
+        slug: r.get("slug"),
+        title: r.get("title"),
+        category: r.get("category"),
+        queue_position: r.get("queue_position"),
+        upload_date: r.get("upload_date"),
+    })
+    .collect();
+
+    Ok(Json(posts))
+}
+
+/// Reorders the publish queue. Listed posts are numbered in order; queued posts that are not
+/// listed keep their place after them. Every listed post must be queued, and listed once.
+#[openapi]
+#[put("/admin/queue/posts", format = "json", data = "<req>")]
+pub async fn reorder_queue(
+    user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    req: Json<QueueOrderRequest>,
+) -> Result<Json<Vec<QueuedPost>>, Status> {
+    let unique: HashSet<i32> = req.post_ids.iter().copied().collect();
+    if unique.len() != req.post_ids.len() {
+        return Err(Status::BadRequest);
+    }
+
+    let mut tx = db.begin().await.map_err(map_db_err)?;
+
+    let queued: Vec<i32> = sqlx::query_scalar(
+        "SELECT id FROM post WHERE status = 'queued' AND id = ANY($1) FOR UPDATE",
+    )
+    .bind(&req.post_ids)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(map_db_err)?;
+    if queued.len() != req.post_ids.len() {
+        return Err(Status::BadRequest);
+    }
+
+    // Push unlisted posts behind the listed ones, keeping their relative order
+    sqlx::query(
+        "UPDATE post SET queue_position = queue_position + $2 \
+        WHERE status = 'queued' AND NOT (id = ANY($1)) AND queue_position IS NOT NULL",
+    )
+    .bind(&req.post_ids)
+    .bind(req.post_ids.len() as i32)
+    .execute(&mut *tx)
+    .await
+    .map_err(map_db_err)?;
+
+    sqlx::query(
+        "UPDATE post SET queue_position = o.position \
+        FROM UNNEST($1::integer[]) WITH ORDINALITY AS o(id, position) \
+        WHERE post.id = o.id AND post.status = 'queued'",
+    )
+    .bind(&req.post_ids)
+    .execute(&mut *tx)
+    .await
+    .map_err(map_db_err)?;
+
+    tx.commit().await.map_err(map_db_err)?;
+
+    queued_posts(user, db).await
+}
+
+/// Publishes the next queued post right away, without waiting for a queue to run
+#[openapi]
+#[post("/admin/queue/publish-next")]
+pub async fn publish_next(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    events: &State<PublishEvents>,
+) -> Result<Json<PostLink>, Status> {
+    let row = sqlx::query(
+        "SELECT n.id, n.slug, p.title FROM publish_next_post() AS n \
+        JOIN post AS p ON p.id = n.id",
+    )
+    .fetch_one(&mut **db)
+    .await
+    .map_err(map_db_err)?; // Not found if the queue is empty
+
+    let slug: String = row.get("slug");
+    rerender_embedders(&mut **db, &slug)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    events.send(PublishEvent {
+        post_id: row.get("id"),
+        slug: slug.clone(),
+        source: PublishSource::Manual,
+    });
+
+    Ok(Json(PostLink { slug, title: row.get("title") }))
+}
@@ -0,0 +1,9 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Deserialize;
+
+/// Represents a new publish queue order
+#[derive(Deserialize, JsonSchema)]
+pub struct QueueOrderRequest {
+    /// Queued post IDs, first to be published first
+    pub post_ids: Vec<i32>,
+}
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a post waiting in the publish queue
+#[derive(Serialize, JsonSchema)]
+pub struct QueuedPost {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub category: String,
+    /// 1-based position in the queue. Posts without one go last, oldest first.
+    pub queue_position: Option<i32>,
+    pub upload_date: DateTime<Utc>,
+}
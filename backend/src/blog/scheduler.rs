@@ -0,0 +1,84 @@
+use super::embed::rerender_embedders;
+use super::publish_event::{PublishEvent, PublishEvents, PublishSource};
+use crate::db::BlogDB;
+use rocket::fairing::AdHoc;
+use rocket_db_pools::{Database, sqlx::{PgPool, Row}};
+use std::time::Duration;
+
+/// How often the scheduler looks for posts to publish
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Fairing that publishes posts whose `publish_date` has passed, ticks the publish queues and
+/// archives expired posts.
+/// Runs inside the server and replaces the old `pg_cron` queue tick, which a migration now
+/// unschedules. Requires `BlogDB` to be attached and `PublishEvents` to be managed.
+pub fn scheduler() -> AdHoc {
+    AdHoc::on_liftoff("Publish Scheduler", |rocket| {
+        Box::pin(async move {
+            let Some(db) = BlogDB::fetch(rocket) else {
+                println!("Publish scheduler not started: database is not attached");
+                return;
+            };
+            let Some(events) = rocket.state::<PublishEvents>().cloned() else {
+                println!("Publish scheduler not started: PublishEvents is not managed");
+                return;
+            };
+            let pool = PgPool::clone(db);
+            let mut shutdown = rocket.shutdown();
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => run(&pool, &events).await,
+                        _ = &mut shutdown => break,
+                    }
+                }
+            });
+        })
+    })
+}
+
+/// Runs one scheduler pass
+async fn run(pool: &PgPool, events: &PublishEvents) {
+    let passes = [
+        ("SELECT * FROM publish_due_posts()", PublishSource::Scheduled),
+        ("SELECT * FROM tick_publish_queue()", PublishSource::Queue),
+    ];
+
+    for (query, source) in passes {
+        match sqlx::query(query).fetch_all(pool).await {
+            Ok(rows) => {
+                for row in rows {
+                    let slug: String = row.get("slug");
+                    rerender(pool, &slug).await;
+                    events.send(PublishEvent { post_id: row.get("id"), slug, source });
+                }
+            }
+            Err(e) => println!("Publish scheduler error: {e}"),
+        }
+    }
+
+    match sqlx::query("SELECT * FROM archive_expired_posts()").fetch_all(pool).await {
+        Ok(rows) => {
+            for row in rows {
+                let id: i32 = row.get("id");
+                let slug: String = row.get("slug");
+                rerender(pool, &slug).await;
+                println!("Archived expired post {id} ({slug})");
+            }
+        }
+        Err(e) => println!("Publish scheduler error: {e}"),
+    }
+}
+
+/// Re-renders the posts that embed a post whose status the scheduler changed
+async fn rerender(pool: &PgPool, slug: &str) {
+    let result = match pool.acquire().await {
+        Ok(mut conn) => rerender_embedders(&mut conn, slug).await,
+        Err(e) => Err(format!("database error: {e}")),
+    };
+    if let Err(e) = result {
+        println!("Publish scheduler error: {e}");
+    }
+}
@@ -0,0 +1,121 @@
+use super::post_link::PostLink;
+use super::post_summary::{POST_SUMMARY_COLUMNS, PostSummary};
+use super::series_index::SeriesIndex;
+use super::series_nav::SeriesNav;
+use super::visibility::{listed, met_roles};
+use crate::auth::{self, Roles};
+use crate::db::{BlogDB, map_db_err};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+use sqlx::PgConnection;
+
+/// Lists the published parts of a series in order
+#[openapi]
+#[get("/series/<slug>")]
+pub async fn series_index(
+    slug: String,
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<SeriesIndex>, Status> {
+    let row = sqlx::query("SELECT id, title FROM series WHERE slug = $1")
+        .bind(&slug)
+        .fetch_one(&mut **db)
+        .await
+        .map_err(map_db_err)?;
+    let id: i32 = row.get("id");
+
+    let parts = sqlx::query(&format!(
+        "SELECT {POST_SUMMARY_COLUMNS} FROM post AS p \
+        WHERE p.series_id = $1 AND p.status = 'published' AND {} \
+        ORDER BY p.series_order, p.id",
+        listed("p", 2)
+    ))
+    .bind(id)
+    .bind(met_roles(user.map(|u| u.role())))
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .iter()
+    .map(PostSummary::from_row)
+    .collect();
+
+    Ok(Json(SeriesIndex { slug, title: row.get("title"), parts }))
+}
+
+/// Returns the series navigation for a post, if it belongs to a series. Parts the viewer cannot
+/// find in lists are skipped.
+pub(super) async fn series_nav(
+    db: &mut Connection<BlogDB>,
+    post_id: i32,
+    viewer: Option<Roles>,
+) -> Result<Option<SeriesNav>, Status> {
+    let row = sqlx::query("SELECT * FROM get_series_nav($1, $2)")
+        .bind(post_id)
+        .bind(met_roles(viewer))
+        .fetch_optional(&mut ***db)
+        .await
+        .map_err(map_db_err)?;
+
+    Ok(row.map(|r| {
+        let link = |slug: &str, title: &str| -> Option<PostLink> {
+            Some(PostLink { slug: r.get::<Option<String>, _>(slug)?, title: r.get(title) })
+        };
+        SeriesNav {
+            slug: r.get("series_slug"),
+            title: r.get("series_title"),
+            position: r.get("position"),
+            total: r.get("total"),
+            previous: link("prev_slug", "prev_title"),
+            next: link("next_slug", "next_title"),
+        }
+    }))
+}
+
+/// Adds a post to the series named in its front matter, creating the series if needed. Removes
+/// the post from its series when `series` is `None`.
+/// # Arguments
+/// - `db`: `&mut PgConnection` - DB connection or transaction
+/// - `post_id`: `i32` - Post to assign
+/// - `series`: `Option<&str>` - Series title
+/// - `order`: `Option<i32>` - Position within the series, appended to the end when `None`
+pub(super) async fn assign_series(
+    db: &mut PgConnection,
+    post_id: i32,
+    series: Option<&str>,
+    order: Option<i32>,
+) -> Result<(), String> {
+    let Some(title) = series else {
+        sqlx::query("UPDATE post SET series_id = NULL, series_order = NULL WHERE id = $1")
+            .bind(post_id)
+            .execute(&mut *db)
+            .await
+            .map_err(|e| format!("database error: {e}"))?;
+        return Ok(());
+    };
+
+    let series_id: i32 = sqlx::query_scalar(
+        "INSERT INTO series (slug, title) VALUES ($1, $2) \
+        ON CONFLICT (slug) DO UPDATE SET title = EXCLUDED.title \
+        RETURNING id",
+    )
+    .bind(slug::slugify(title))
+    .bind(title)
+    .fetch_one(&mut *db)
+    .await
+    .map_err(|e| format!("database error: {e}"))?;
+
+    sqlx::query(
+        "UPDATE post SET series_id = $1, series_order = COALESCE($2, \
+            (SELECT COALESCE(MAX(series_order), 0) + 1 FROM post WHERE series_id = $1 AND id <> $3)) \
+        WHERE id = $3",
+    )
+    .bind(series_id)
+    .bind(order)
+    .bind(post_id)
+    .execute(&mut *db)
+    .await
+    .map_err(|e| format!("database error: {e}"))?;
+
+    Ok(())
+}
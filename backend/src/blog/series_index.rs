@@ -0,0 +1,11 @@
+use super::post_summary::PostSummary;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a series and its published parts in order
+#[derive(Serialize, JsonSchema)]
+pub struct SeriesIndex {
+    pub slug: String,
+    pub title: String,
+    pub parts: Vec<PostSummary>,
+}
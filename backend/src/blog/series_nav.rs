@@ -0,0 +1,16 @@
+use super::post_link::PostLink;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents the position of a post within its series, e.g. "part 3 of 5"
+#[derive(Serialize, JsonSchema)]
+pub struct SeriesNav {
+    pub slug: String,
+    pub title: String,
+    /// 1-based position of the post among the published parts
+    pub position: i64,
+    /// Number of published parts
+    pub total: i64,
+    pub previous: Option<PostLink>,
+    pub next: Option<PostLink>,
+}
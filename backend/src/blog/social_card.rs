@@ -0,0 +1,166 @@
+use crate::config::config;
+use crate::media;
+use ab_glyph::{Font, FontVec, GlyphId, PxScale, ScaleFont, point};
+use tiny_skia::{Color, Paint, Pixmap, Rect, Transform};
+
+/// Card size recommended for Open Graph and Twitter `summary_large_image`
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+const MARGIN: f32 = 80.0;
+
+/// Bump when the template changes so cached cards are redrawn
+const TEMPLATE_VERSION: &str = "1";
+
+const BACKGROUND: [u8; 3] = [30, 30, 46];
+const ACCENT: [u8; 3] = [137, 180, 250];
+const FOREGROUND: [u8; 3] = [205, 214, 244];
+
+/// Returns the URL path of the social card for a post, drawing it if it is not cached yet.
+/// Reading the font and rasterizing run on the blocking pool so no Rocket worker is held up.
+/// # Returns
+/// - `Option<String>` - `None` if no font is configured or the card could not be drawn
+pub(super) async fn social_card(title: &str, category: &str) -> Option<String> {
+    let font_path = config().media.font?;
+    let site = config().site.name;
+    let key =
+        format!("social-card\n{TEMPLATE_VERSION}\n{font_path}\n{site}\n{category}\n{title}");
+    let (title, category) = (title.to_string(), category.to_string());
+
+    let card = tokio::task::spawn_blocking(move || {
+        media::cached(key.as_bytes(), "png", || {
+            let font = FontVec::try_from_vec(std::fs::read(&font_path).ok()?).ok()?;
+            draw_card(&font, &title, &category)
+        })
+    })
+    .await;
+    match card {
+        Ok(Ok(card)) => card,
+        Ok(Err(e)) => {
+            println!("Could not store social card: {e}");
+            None
+        }
+        Err(e) => {
+            println!("Could not draw social card: {e}");
+            None
+        }
+    }
+}
+
+/// Draws the card template: category, wrapped title and site name
+fn draw_card(font: &impl Font, title: &str, category: &str) -> Option<Vec<u8>> {
+    let mut pixmap = Pixmap::new(WIDTH, HEIGHT)?;
+    pixmap.fill(Color::from_rgba8(BACKGROUND[0], BACKGROUND[1], BACKGROUND[2], 255));
+
+    // Accent bar down the left edge
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(ACCENT[0], ACCENT[1], ACCENT[2], 255);
+    pixmap.fill_rect(Rect::from_xywh(0.0, 0.0, 24.0, HEIGHT as f32)?, &paint, Transform::identity(), None);
+
+    draw_text(&mut pixmap, font, 36.0, MARGIN, MARGIN, &category.to_uppercase(), ACCENT);
+
+    // Title, shrunk until it fits in four lines
+    let max_width = WIDTH as f32 - 2.0 * MARGIN;
+    let (size, lines) = [72.0, 60.0, 48.0]
+        .into_iter()
+        .map(|size| (size, wrap(font, size, title, max_width)))
+        .find(|(_, lines)| lines.len() <= 4)
+        .unwrap_or_else(|| (48.0, wrap(font, 48.0, title, max_width).into_iter().take(4).collect()));
+    let mut y = MARGIN + 80.0;
+    for line in lines {
+        draw_text(&mut pixmap, font, size, MARGIN, y, &line, FOREGROUND);
+        y += size * 1.2;
+    }
+
+    let site = config().site.name;
+    draw_text(&mut pixmap, font, 32.0, MARGIN, HEIGHT as f32 - MARGIN - 32.0, &site, FOREGROUND);
+
+    pixmap.encode_png().ok()
+}
+
+/// Splits text into lines no wider than `max_width` pixels
+fn wrap(font: &impl Font, size: f32, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{line} {word}") };
+        if !line.is_empty() && text_width(font, size, &candidate) > max_width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Width of a line of text in pixels
+fn text_width(font: &impl Font, size: f32, text: &str) -> f32 {
+    let scaled = font.as_scaled(PxScale::from(size));
+    let mut width = 0.0;
+    let mut prev: Option<GlyphId> = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(p) = prev {
+            width += scaled.kern(p, id);
+        }
+        width += scaled.h_advance(id);
+        prev = Some(id);
+    }
+    width
+}
+
+/// Draws a line of text with its top-left corner at `(x, y)`
+fn draw_text(
+    pixmap: &mut Pixmap,
+    font: &impl Font,
+    size: f32,
+    x: f32,
+    y: f32,
+    text: &str,
+    color: [u8; 3],
+) {
+    let scale = PxScale::from(size);
+    let scaled = font.as_scaled(scale);
+    let mut caret = point(x, y + scaled.ascent());
+    let mut prev: Option<GlyphId> = None;
+
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(p) = prev {
+            caret.x += scaled.kern(p, id);
+        }
+        let glyph = id.with_scale_and_position(scale, caret);
+        caret.x += scaled.h_advance(id);
+        prev = Some(id);
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            blend(
+                pixmap,
+                bounds.min.x as i32 + gx as i32,
+                bounds.min.y as i32 + gy as i32,
+                color,
+                coverage,
+            );
+        });
+    }
+}
+
+/// Blends a text pixel onto the opaque card background
+fn blend(pixmap: &mut Pixmap, x: i32, y: i32, color: [u8; 3], coverage: f32) {
+    if x < 0 || y < 0 || x >= WIDTH as i32 || y >= HEIGHT as i32 {
+        return;
+    }
+    let i = (y as usize * WIDTH as usize + x as usize) * 4;
+    let data = pixmap.data_mut();
+    let coverage = coverage.clamp(0.0, 1.0);
+    for (channel, src) in color.into_iter().enumerate() {
+        let dst = data[i + channel] as f32;
+        data[i + channel] = (dst + (src as f32 - dst) * coverage).round() as u8;
+    }
+}
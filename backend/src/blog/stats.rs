@@ -0,0 +1,97 @@
+use super::upload::comrak_options;
+use comrak::{Arena, nodes::NodeValue, parse_document};
+
+/// Average adult reading speed in words per minute
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Automatic excerpts are cut at a sentence boundary within this many characters
+const EXCERPT_LENGTH: usize = 200;
+
+/// Reading statistics and summary of a post, shown on index pages and feeds
+pub(super) struct PostStats {
+    pub word_count: i32,
+    /// Estimated reading time in minutes
+    pub reading_time: i32,
+    pub excerpt: String,
+}
+
+impl PostStats {
+    /// Computes the statistics of a markdown body. `summary` from front matter replaces the
+    /// automatic excerpt.
+    pub fn new(md: &str, summary: Option<&str>) -> Self {
+        let (text, prose) = plain_text(md);
+        let word_count = text.split_whitespace().count();
+        let reading_time = word_count.div_ceil(WORDS_PER_MINUTE).max(1);
+        let excerpt = match summary {
+            Some(summary) => summary.trim().to_string(),
+            None => excerpt(&prose),
+        };
+
+        Self {
+            word_count: word_count as i32,
+            reading_time: reading_time as i32,
+            excerpt,
+        }
+    }
+}
+
+/// Returns the plain text of a markdown document
+/// # Returns
+/// - `(String, String)` - All text, and the text of paragraphs only. Headings and code are left
+///   out of the second so they don't end up in excerpts.
+fn plain_text(md: &str) -> (String, String) {
+    let arena = Arena::new();
+    let options = comrak_options();
+    let root = parse_document(&arena, md, &options);
+
+    let mut text = String::new();
+    let mut prose = String::new();
+    for node in root.descendants() {
+        // `ancestors` includes the node itself
+        let in_paragraph = node
+            .ancestors()
+            .any(|a| matches!(a.data.borrow().value, NodeValue::Paragraph));
+        let piece = match node.data.borrow().value {
+            NodeValue::Text(ref t) => t.to_string(),
+            NodeValue::Code(ref c) => c.literal.clone(),
+            NodeValue::CodeBlock(ref c) => c.literal.clone(),
+            NodeValue::SoftBreak | NodeValue::LineBreak => " ".into(),
+            // Keep words in separate blocks apart
+            NodeValue::Paragraph | NodeValue::Heading(_) | NodeValue::Item(_) => " ".into(),
+            _ => continue,
+        };
+        text.push_str(&piece);
+        if in_paragraph {
+            prose.push_str(&piece);
+        }
+    }
+
+    (text, prose)
+}
+
+/// Cuts plain text down to an excerpt, ending on a full sentence when one fits
+fn excerpt(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= EXCERPT_LENGTH {
+        return text;
+    }
+
+    let cut: String = text.chars().take(EXCERPT_LENGTH).collect();
+    // Last sentence end that is followed by a space in the full text
+    let sentence_end = cut
+        .char_indices()
+        .filter(|(i, c)| {
+            matches!(c, '.' | '!' | '?') && text[i + c.len_utf8()..].starts_with(' ')
+        })
+        .map(|(i, c)| i + c.len_utf8())
+        .last();
+
+    match sentence_end {
+        Some(end) => cut[..end].to_string(),
+        // No sentence fits; fall back to the last whole word
+        None => match cut.rfind(' ') {
+            Some(end) => format!("{}…", &cut[..end]),
+            None => format!("{cut}…"),
+        },
+    }
+}
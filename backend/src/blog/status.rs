@@ -0,0 +1,153 @@
+use super::embed::rerender_embedders;
+use super::manage::authorize_owner;
+use super::post_status::PostStatus;
+use super::publish_event::{PublishEvent, PublishEvents, PublishSource};
+use super::status_change::StatusChange;
+use super::status_request::StatusRequest;
+use crate::auth;
+use crate::db::{BlogDB, map_db_err};
+use chrono::{DateTime, Utc};
+use rocket::{State, http::Status, serde::json::Json};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+use sqlx::{Connection as _, PgConnection};
+
+/// Moves a post to another status. Only the original author or an admin may trash a post.
+#[openapi]
+#[put("/blog/post/<id>/status", format = "json", data = "<req>")]
+pub async fn set_status(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    events: &State<PublishEvents>,
+    id: i32,
+    req: Json<StatusRequest>,
+) -> Result<Json<Vec<StatusChange>>, Status> {
+    authorize_owner(&mut db, &user, id, req.status != PostStatus::Trashed).await?;
+
+    let row = sqlx::query("SELECT status, slug, publish_date, expires_at FROM post WHERE id = $1")
+        .bind(id)
+        .fetch_one(&mut **db)
+        .await
+        .map_err(map_db_err)?;
+    let from: PostStatus = row.get("status");
+    let slug: String = row.get("slug");
+    let publish_date: Option<DateTime<Utc>> = row.get("publish_date");
+    from.transition(req.status)?;
+    // The scheduler would archive a post that is already expired right away
+    let expires_at = req.expires_at.unwrap_or_else(|| row.get("expires_at"));
+    if req.status == PostStatus::Published && expires_at.is_some_and(|e| e <= Utc::now()) {
+        return Err(Status::Conflict);
+    }
+    if req.status == PostStatus::Scheduled && req.publish_date.is_none() && publish_date.is_none()
+    {
+        return Err(Status::BadRequest);
+    }
+
+    // Archived posts keep their original date when they come back
+    let stamp = req.status == PostStatus::Published && from != PostStatus::Archived;
+    // Only move the post if nothing else moved it since it was read, so the transition check
+    // and the recorded history both hold
+    let mut tx = db.begin().await.map_err(map_db_err)?;
+    let updated = sqlx::query(
+        "UPDATE post SET status = $1, \
+        publish_date = CASE WHEN $2 THEN COALESCE($3, NOW()) ELSE COALESCE($3, publish_date) END, \
+        queue_position = CASE WHEN $1 = 'queued' \
+            THEN (SELECT COALESCE(MAX(queue_position), 0) + 1 FROM post WHERE status = 'queued') \
+            ELSE NULL END, \
+        expires_at = CASE WHEN $6 THEN $7 ELSE expires_at END \
+        WHERE id = $4 AND status = $5",
+    )
+    .bind(req.status)
+    .bind(stamp)
+    .bind(req.publish_date)
+    .bind(id)
+    .bind(from)
+    .bind(req.expires_at.is_some())
+    .bind(req.expires_at.flatten())
+    .execute(&mut *tx)
+    .await
+    .map_err(map_db_err)?;
+    if updated.rows_affected() == 0 {
+        return Err(Status::Conflict);
+    }
+    record_status(&mut tx, id, Some(from), req.status, Some(user.id()))
+        .await
+        .map_err(map_db_err)?;
+    // Posts that inline this one only show it while it's published
+    rerender_embedders(&mut tx, &slug)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    tx.commit().await.map_err(map_db_err)?;
+
+    if req.status == PostStatus::Published {
+        events.send(PublishEvent {
+            post_id: id,
+            slug,
+            source: PublishSource::Manual,
+        });
+    }
+
+    status_history(user, db, id).await
+}
+
+/// Lists the status changes of a post, oldest first
+#[openapi]
+#[get("/blog/post/<id>/status")]
+pub async fn status_history(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    id: i32,
+) -> Result<Json<Vec<StatusChange>>, Status> {
+    authorize_owner(&mut db, &user, id, true).await?;
+
+    let changes = sqlx::query(
+        "SELECT c.from_status, c.to_status, u.username, c.changed_at \
+        FROM post_status_change AS c \
+        LEFT JOIN users AS u ON u.id = c.changed_by \
+        WHERE c.post_id = $1 \
+        ORDER BY c.changed_at, c.id",
+    )
+    .bind(id)
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .into_iter()
+    .map(|r| StatusChange {
+        from: r.get("from_status"),
+        to: r.get("to_status"),
+        changed_by: r.get("username"),
+        changed_at: r.get("changed_at"),
+    })
+    .collect();
+
+    Ok(Json(changes))
+}
+
+/// Adds an entry to a post's status history
+/// # Arguments
+/// - `conn`: `&mut PgConnection` - DB connection or transaction, so the entry can be written
+///   together with the change itself
+/// - `post_id`: `i32` - Post that changed
+/// - `from`: `Option<PostStatus>` - Previous status, `None` for a new post
+/// - `to`: `PostStatus` - New status
+/// - `user_id`: `Option<i32>` - User who made the change, `None` for the scheduler
+pub(super) async fn record_status(
+    conn: &mut PgConnection,
+    post_id: i32,
+    from: Option<PostStatus>,
+    to: PostStatus,
+    user_id: Option<i32>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO post_status_change (post_id, from_status, to_status, changed_by) \
+        VALUES ($1, $2, $3, $4)",
+    )
+    .bind(post_id)
+    .bind(from)
+    .bind(to)
+    .bind(user_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
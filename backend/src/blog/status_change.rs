@@ -0,0 +1,15 @@
+use super::post_status::PostStatus;
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents an entry in a post's status history
+#[derive(Serialize, JsonSchema)]
+pub struct StatusChange {
+    /// `None` when the post was created
+    pub from: Option<PostStatus>,
+    pub to: PostStatus,
+    /// Username of the user who made the change. `None` for the scheduler or deleted users.
+    pub changed_by: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
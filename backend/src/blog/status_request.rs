@@ -0,0 +1,26 @@
+use super::post_status::PostStatus;
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Deserializer};
+
+/// Represents a request to move a post to another status
+#[derive(Deserialize, JsonSchema)]
+pub struct StatusRequest {
+    pub status: PostStatus,
+    /// New publish date. Required to schedule a post that has none; defaults to now when
+    /// publishing.
+    pub publish_date: Option<DateTime<Utc>>,
+    /// New expiry date, or `null` to remove it. Left out, the current one is kept. Posts whose
+    /// expiry has passed need a new one before they can be published again.
+    #[serde(default, deserialize_with = "present")]
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Tells a field sent as `null` apart from one left out
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,63 @@
+use super::post_summary::{POST_SUMMARY_COLUMNS, PostSummary};
+use super::visibility::{listed, met_roles};
+use crate::auth;
+use crate::db::{BlogDB, map_db_err};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::Connection;
+use rocket_okapi::openapi;
+use sqlx::PgConnection;
+
+/// Lists the published posts with the given tag, newest first. Tags are matched by slug.
+#[openapi]
+#[get("/tag/<tag>")]
+pub async fn tag(
+    tag: String,
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<Vec<PostSummary>>, Status> {
+    let posts = sqlx::query(&format!(
+        "SELECT {POST_SUMMARY_COLUMNS} FROM post AS p \
+        JOIN post_tag AS t ON t.post_id = p.id \
+        WHERE t.tag_slug = $1 AND p.status = 'published' AND {} \
+        ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC",
+        listed("p", 2)
+    ))
+    .bind(slug::slugify(&tag))
+    .bind(met_roles(user.map(|u| u.role())))
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .iter()
+    .map(PostSummary::from_row)
+    .collect();
+
+    Ok(Json(posts))
+}
+
+/// Replaces the tags recorded for a post
+pub(super) async fn set_tags(
+    db: &mut PgConnection,
+    post_id: i32,
+    tags: &[String],
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM post_tag WHERE post_id = $1")
+        .bind(post_id)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    let slugs: Vec<String> = tags.iter().map(|t| slug::slugify(t)).collect();
+    sqlx::query(
+        "INSERT INTO post_tag (post_id, tag, tag_slug) \
+        SELECT $1, t.tag, t.tag_slug FROM UNNEST($2::text[], $3::text[]) AS t(tag, tag_slug) \
+        ON CONFLICT DO NOTHING",
+    )
+    .bind(post_id)
+    .bind(tags)
+    .bind(&slugs)
+    .execute(&mut *db)
+    .await
+    .map_err(|e| format!("database error: {e}"))?;
+
+    Ok(())
+}
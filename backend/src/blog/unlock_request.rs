@@ -0,0 +1,8 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Deserialize;
+
+/// Represents an attempt to unlock a password-protected post
+#[derive(Deserialize, JsonSchema)]
+pub struct UnlockRequest {
+    pub password: String,
+}
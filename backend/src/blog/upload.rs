@@ -1,94 +1,204 @@
-use crate::auth;
+use super::embed::{EmbedSources, embed_targets, load_embeds, rerender_embedders, set_embeds, transclude};
+use super::front_matter::FrontMatter;
+use super::lint::{lint_markdown, lint_publish_date, lint_embeds, lint_wikilinks};
+use super::lint_warning::LintWarning;
+use super::links::set_links;
+use super::obsidian::{extract_tags, highlight, restore_highlights, strip_comments};
+use super::post_status::PostStatus;
+use super::status::record_status;
+use super::tags::set_tags;
+use super::series::assign_series;
+use super::social_card::social_card;
+use super::stats::PostStats;
+use super::upload_request::UploadRequest;
+use super::upload_response::UploadResponse;
+use crate::auth::{self, Roles};
 use crate::db::BlogDB;
 use ammonia::Builder as HtmlSanitizer;
-use comrak::{
-    markdown_do_html,
-    ComrakExtensionOptions,
-    ComrakOptions,
-    ComrakParseOptions,
-    ComrakRenderOptions,
-};
+use chrono::Utc;
+use comrak::{markdown_to_html, Options as ComrakOptions};
 use regex::Regex;
 use rocket::{
+    data::{Data, ToByteUnit},
     form::Form,
     fs::TempFile,
-    serde::{ Deserialize, json::Json },
-    rocket::{ post, routes, Build, Rocket },
+    serde::json::Json,
+};
+use rocket_db_pools::{Connection, sqlx::{Postgres, QueryBuilder, Row}};
+use rocket_okapi::{
+    okapi::schemars::{self, JsonSchema},
+    openapi,
 };
-use rocket_db_pools::{Connection, sqlx::Row};
+use sqlx::{Connection as _, PgConnection};
+use std::path::Path;
 
-#[derive(FromForm)]
-struct PostUpload<'r> {
+#[derive(FromForm, JsonSchema)]
+pub struct PostUpload<'r> {
+    #[schemars(with = "String")]
     file: TempFile<'r>,
-    publish: bool,
-    queued: bool,
+    status: Option<PostStatus>,
+    strict: bool,
+}
+
+/// Options shared by every upload endpoint
+#[derive(Clone, Copy)]
+pub(super) struct UploadFlags {
+    /// Status to create the post with. Defaults to `Scheduled` if the front matter has a future
+    /// `publish_date`, `Draft` otherwise.
+    pub status: Option<PostStatus>,
+    /// Refuse the upload if there are any lint warnings
+    pub strict: bool,
+}
+
+/// Markdown upload after it has been run through the rendering pipeline
+pub(super) struct RenderedPost {
+    pub slug: String,
+    pub title: String,
+    pub body: RenderedBody,
+    pub meta: FrontMatter,
+    /// Lint warnings for the markdown itself
+    pub warnings: Vec<LintWarning>,
 }
 
-#[derive(Debug, Deserialize)]
-struct FrontMatter {
-    title: Option<String>,
-    category: Option<String>,
-    publish_date: Option<DateTime<Utc>>,
+/// Markdown body after it has been rendered to HTML
+pub(super) struct RenderedBody {
+    /// Sanitized HTML
+    pub html: String,
+    /// Slugs of the posts linked with wikilinks
+    pub links: Vec<String>,
+    /// Slugs of the posts inlined with `![[embeds]]`
+    pub embeds: Vec<String>,
+    /// Front matter tags and inline `#tags`
+    pub tags: Vec<String>,
+    pub stats: PostStats,
 }
 
+/// Uploads a markdown file as a multipart form
+#[openapi]
 #[post("/blog/upload", data = "<form>")]
 pub async fn upload(
-    user: auth::AuthUser,
+    user: auth::RequireRole<auth::Author>,
     mut db: Connection<BlogDB>,
-    mut form: Form<PostUpload<'_>>) -> Json<serde_json::Value> {
+    form: Form<PostUpload<'_>>,
+) -> Json<UploadResponse> {
     // Read uploaded file to memory
     let mut md = String::new();
-    if let Err(e) = form.file.open(1 << 22)
-        .await
-        .and_then(|mut f| Box::pin(async move {
-            use tokio::io::AsyncReadExt;
-            f.read_to_string(&mut md).await
-        })) {
-            // An error occured
-            return Json(json!({ "ok": false, "error": format!("read failure: {e}") }));
+    let read = async {
+        use tokio::io::AsyncReadExt;
+        form.file.open().await?.read_to_string(&mut md).await
+    };
+    if let Err(e) = read.await {
+        // An error occured
+        return Json(UploadResponse::error(format!("read failure: {e}")));
     }
 
-    // Split front matter
-    let (fm, body) = match split_front_matter(&md) {
-        Ok((fm, body)) => (fm, body),
-        Err(e) => return Json(json!({ "ok": false, "error": e })),
-    };
-    // Parse front matter
-    let meta: FrontMatter = match fm
-        .map(|y| serde_yaml::from_str::<FrontMatter>(y))
-        .transpose()
-    {
-        Ok(m) => m.unwrap_or(FrontMatter { 
-            title: None, 
-            category: None, 
-            publish_date: None,
-        }),
-        Err(e) => return Json(json!({ "ok": false, "error": format!("bad front matter: {e}")})),
+    let filename = form.file.name().unwrap_or("untitled.md");
+    let flags = UploadFlags { status: form.status, strict: form.strict };
+    Json(
+        process_upload(&mut db, user.id(), &md, filename, flags)
+            .await
+            .unwrap_or_else(UploadResponse::error),
+    )
+}
+
+/// Uploads a post sent as JSON. Meant for scripts and editor plugins.
+#[openapi]
+#[post("/blog/upload/json", format = "json", data = "<req>")]
+pub async fn upload_json(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    req: Json<UploadRequest>,
+) -> Json<UploadResponse> {
+    let filename = req.filename.as_deref().unwrap_or("untitled.md");
+    let flags = UploadFlags { status: req.status, strict: req.strict };
+    Json(
+        process_upload(&mut db, user.id(), &req.markdown, filename, flags)
+            .await
+            .unwrap_or_else(UploadResponse::error),
+    )
+}
+
+/// Uploads a post sent as a raw `text/markdown` body. Options are passed in the query string.
+#[openapi]
+#[post(
+    "/blog/upload/markdown?<filename>&<status>&<strict>",
+    format = "text/markdown",
+    data = "<data>"
+)]
+pub async fn upload_markdown(
+    user: auth::RequireRole<auth::Author>,
+    mut db: Connection<BlogDB>,
+    data: Data<'_>,
+    filename: Option<String>,
+    status: Option<PostStatus>,
+    strict: Option<bool>,
+) -> Json<UploadResponse> {
+    // Read the body, refusing anything that was cut off by the limit
+    let md = match data.open(4.mebibytes()).into_string().await {
+        Ok(s) if s.is_complete() => s.into_inner(),
+        Ok(_) => return Json(UploadResponse::error("upload too large")),
+        Err(e) => return Json(UploadResponse::error(format!("read failure: {e}"))),
     };
 
-    // Resolve Obsidian features
-    let body = rewrite_wikilinks(&body);
-    
-    // Make sure we have a title
-    if meta.title == None {
-        let filename = form.file.name().unwrap_or("untitled.md");
-        if let Some(title) = infer_title(&body, &filename) {
-            meta.title = title;
-        } else {
-            return Json(json!({ "ok": false, "error": "could not infer title".into() }));
-        }
+    let filename = filename.as_deref().unwrap_or("untitled.md");
+    let flags = UploadFlags { status, strict: strict.unwrap_or(false) };
+    Json(
+        process_upload(&mut db, user.id(), &md, filename, flags)
+            .await
+            .unwrap_or_else(UploadResponse::error),
+    )
+}
+
+/// Runs uploaded markdown through the publishing pipeline and writes the post to the database.
+/// Shared by every upload endpoint.
+/// # Arguments
+/// - `db`: `&mut Connection<BlogDB>` - Rocket Sqlx_pools DB
+/// - `author_id`: `i32` - ID of the uploading user, recorded as the post author
+/// - `md`: `&str` - Markdown source, including front matter
+/// - `filename`: `&str` - Original file name, used to infer a missing title
+/// - `flags`: `UploadFlags` - Publishing and lint options
+/// # Returns
+/// - `Result<UploadResponse, String>` - Post id, slug and lint warnings, or the warnings alone
+///   if a strict upload was refused
+async fn process_upload(
+    db: &mut Connection<BlogDB>,
+    author_id: i32,
+    md: &str,
+    filename: &str,
+    flags: UploadFlags,
+) -> Result<UploadResponse, String> {
+    let embeds = load_embeds(&mut ***db, md).await?;
+    let post = render_post(md, filename, &embeds)?;
+    let coauthors = resolve_coauthors(db, &post.meta.coauthors, author_id).await?;
+    let RenderedPost { slug, title, body, meta, mut warnings } = post;
+
+    let status = flags.status.unwrap_or(match meta.publish_date {
+        Some(date) if date > Utc::now() => PostStatus::Scheduled,
+        _ => PostStatus::Draft,
+    });
+    if status != PostStatus::Draft && PostStatus::Draft.transition(status).is_err() {
+        return Err(format!("posts cannot be uploaded as {status:?}"));
+    }
+    if status == PostStatus::Scheduled && meta.publish_date.is_none() {
+        return Err("scheduled posts need a publish_date".into());
     }
 
-    // Markdown -> HTML
-    let body = md_do_html(&body);
+    // Lint
+    let publishing = matches!(status, PostStatus::Published | PostStatus::Scheduled);
+    warnings.extend(lint_publish_date(&meta, publishing));
+    warnings.extend(lint_wikilinks(db, &body.links, &slug).await?);
+    warnings.extend(lint_embeds(&body.embeds, &embeds));
+    if flags.strict && !warnings.is_empty() {
+        return Ok(UploadResponse::rejected(warnings));
+    }
 
-    // Sanitize HTML
-    let body = sanitize(&body);
+    let card = card_for(&title, &meta).await;
 
-    // Generate the slug
-    let slug = slug::slugify(&meta.title);
+    let source = redact_password(md)?;
+
+    // Write to db, all or nothing
+    let mut tx = db.begin().await.map_err(|e| format!("database error: {e}"))?;
 
-    // Write to db
     // Build the query
     let mut qb: QueryBuilder<Postgres> = QueryBuilder::new("INSERT INTO post (");
 
@@ -97,51 +207,230 @@ pub async fn upload(
     sep.push("slug");
     sep.push("title");
     sep.push("body");
-    sep.push("published");
-    sep.push("queued");
+    sep.push("source");
+    sep.push("word_count");
+    sep.push("reading_time");
+    sep.push("excerpt");
+    sep.push("author_id");
+    sep.push("status");
+    sep.push("description");
+    sep.push("cover_image");
+    sep.push("canonical_url");
+    sep.push("social_card");
+    sep.push("visibility");
+    sep.push("min_role");
+    sep.push("password_hash");
     if meta.category.is_some() { sep.push("category"); }
     if meta.publish_date.is_some() { sep.push("publish_date"); }
-    
+    if meta.expires_at.is_some() { sep.push("expires_at"); }
+    if meta.review_by.is_some() { sep.push("review_by"); }
+    if status == PostStatus::Queued { sep.push("queue_position"); }
+    let stamp = status == PostStatus::Published && meta.publish_date.is_none();
+    if stamp { sep.push("publish_date"); }
+
     // Values
     qb.push(") VALUES (");
     let mut v = qb.separated(", ");
     v.push_bind(slug.clone());
-    v.push_bind(meta.title.clone());
-    v.push_bind(body);
-    if form.publish {
-        v.push_bind(true);
-        v.push_bind(false);
-    } else if form.queue {
-        v.push_bind(false);
-        v.push_bind(true);
-    } else {
-        v.push_bind(false);
-        v.push_bind(false);
-    }
+    v.push_bind(title.clone());
+    v.push_bind(body.html);
+    v.push_bind(source);
+    v.push_bind(body.stats.word_count);
+    v.push_bind(body.stats.reading_time);
+    v.push_bind(body.stats.excerpt.clone());
+    v.push_bind(author_id);
+    v.push_bind(status);
+    v.push_bind(meta.description.clone());
+    v.push_bind(meta.cover_image.clone());
+    v.push_bind(meta.canonical_url.clone());
+    v.push_bind(card);
+    v.push_bind(meta.visibility.unwrap_or_default());
+    v.push_bind(meta.min_role.unwrap_or(Roles::Guest));
+    v.push_bind(meta.password.as_deref().filter(|p| !p.is_empty()).map(auth::hash_password));
     if let Some(c) = meta.category.clone() { v.push_bind(c); }
-    if let Some(pd) = meta.category.clone() { v.push_bind(pd); }
+    if let Some(pd) = meta.publish_date { v.push_bind(pd); }
+    if let Some(ea) = meta.expires_at { v.push_bind(ea); }
+    if let Some(rb) = meta.review_by { v.push_bind(rb); }
+    // New queued posts go to the back of the queue
+    if status == PostStatus::Queued {
+        v.push("(SELECT COALESCE(MAX(queue_position), 0) + 1 FROM post WHERE status = 'queued')");
+    }
+    if stamp { v.push("NOW()"); }
 
     // Finish building query and run
     qb.push(") RETURNING id");
-    let row = match qb.build().fetch_one(&mut **db).await {
-        Ok(r) => r,
-        Err(e) => return Json(json!({ "ok": false, "error": format!("database error: {e}") })),
+    let row = qb
+        .build()
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+    let id: i32 = row
+        .try_get("id")
+        .map_err(|e| format!("database error: {e}"))?;
+
+    record_status(&mut tx, id, None, status, Some(author_id))
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+    set_coauthors(&mut tx, id, &coauthors).await?;
+    assign_series(&mut tx, id, meta.series.as_deref(), meta.series_order).await?;
+    set_links(&mut tx, id, &body.links).await?;
+    set_embeds(&mut tx, id, &body.embeds).await?;
+    set_tags(&mut tx, id, &body.tags).await?;
+
+    // Posts waiting on this one can now inline it
+    rerender_embedders(&mut tx, &slug).await?;
+
+    tx.commit().await.map_err(|e| format!("database error: {e}"))?;
+
+    Ok(UploadResponse::ok(id, slug, warnings))
+}
+
+/// Returns the generated social card for posts without a cover image
+pub(super) async fn card_for(title: &str, meta: &FrontMatter) -> Option<String> {
+    if meta.cover_image.is_some() {
+        return None;
     }
-    let id: i64 = match row.try_get("id") {
-        Ok(i) => i,
-        Err(e) => return Json(json!({ "ok": false, "error": format!("database error: {e}") })),
+    social_card(title, meta.category.as_deref().unwrap_or("general")).await
+}
+
+/// Renders markdown and its front matter into a post ready to be stored
+/// # Arguments
+/// - `md`: `&str` - Markdown source, including front matter
+/// - `filename`: `&str` - Original file name, used to infer a missing title
+/// - `embeds`: `&EmbedSources` - Sources of embedded posts, from `load_embeds`
+pub(super) fn render_post(
+    md: &str,
+    filename: &str,
+    embeds: &EmbedSources,
+) -> Result<RenderedPost, String> {
+    let (meta, body) = parse_front_matter(md)?;
+
+    // Make sure we have a title
+    let title = match meta.title.clone() {
+        Some(title) => title,
+        None => infer_title(body, filename).ok_or("could not infer title")?,
     };
 
-    Json(json!({ "ok": true, "post_id": id, "slug": slug }))
+    let warnings = lint_markdown(&strip_comments(body), &title);
+    let body = render_body(body, &meta, embeds);
+
+    // Generate the slug
+    let slug = slug::slugify(&title);
+
+    Ok(RenderedPost { slug, title, body, meta, warnings })
+}
+
+/// Renders a markdown body, without front matter, to sanitized HTML
+/// # Arguments
+/// - `md`: `&str` - Markdown body
+/// - `meta`: `&FrontMatter` - Front matter of the post
+/// - `embeds`: `&EmbedSources` - Sources of embedded posts, from `load_embeds`
+pub(super) fn render_body(md: &str, meta: &FrontMatter, embeds: &EmbedSources) -> RenderedBody {
+    // Private comments go first so nothing inside them is picked up
+    let md = strip_comments(md);
+
+    // Only the post's own tags are recorded; tags in embedded posts are just rewritten
+    let (md, inline_tags) = extract_tags(&md, meta.tag_links);
+    let mut tags = meta.tags.clone();
+    for tag in inline_tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    // Resolve Obsidian features
+    let embed_slugs = embed_targets(&md);
+    let md = transclude(&md, embeds, &mut Vec::new());
+    let (md, _) = extract_tags(&md, meta.tag_links);
+    let (md, links) = rewrite_wikilinks(&md);
+    let stats = PostStats::new(&md, meta.summary.as_deref());
+    let md = highlight(&md);
+
+    // Markdown -> HTML
+    let html = restore_highlights(&md_to_html(&md));
+
+    // Sanitize HTML
+    let html = sanitize(&html);
+
+    RenderedBody { html, links, embeds: embed_slugs, tags, stats }
+}
+
+/// Looks up the user IDs of the co-authors named in front matter. The uploading author is
+/// skipped if they credit themselves.
+/// # Returns
+/// - `Result<Vec<i32>, String>` - Errors if any username does not exist
+pub(super) async fn resolve_coauthors(
+    db: &mut Connection<BlogDB>,
+    usernames: &[String],
+    author_id: i32,
+) -> Result<Vec<i32>, String> {
+    if usernames.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query("SELECT id, username FROM users WHERE username = ANY($1)")
+        .bind(usernames)
+        .fetch_all(&mut ***db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    // Every listed co-author must exist
+    let found: Vec<String> = rows.iter().map(|r| r.get("username")).collect();
+    if let Some(missing) = usernames.iter().find(|u| !found.contains(u)) {
+        return Err(format!("unknown co-author: {missing}"));
+    }
+
+    Ok(rows
+        .iter()
+        .map(|r| r.get::<i32, _>("id"))
+        .filter(|id| *id != author_id)
+        .collect())
 }
 
-fn split_front_matter(md: &str) -> Result<(Option<&str>, &str), String> {
+/// Replaces the co-authors of a post
+pub(super) async fn set_coauthors(
+    db: &mut PgConnection,
+    post_id: i32,
+    coauthors: &[i32],
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM post_author WHERE post_id = $1")
+        .bind(post_id)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    sqlx::query("INSERT INTO post_author (post_id, user_id) SELECT $1, UNNEST($2::integer[])")
+        .bind(post_id)
+        .bind(coauthors)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| format!("database error: {e}"))?;
+
+    Ok(())
+}
+
+/// Splits and parses front matter. Missing front matter gives the defaults.
+/// # Returns
+/// - `Result<(FrontMatter, &str), String>` - Front matter and the markdown body
+pub(super) fn parse_front_matter(md: &str) -> Result<(FrontMatter, &str), String> {
+    // Split front matter
+    let (fm, body) = split_front_matter(md)?;
+    // Parse front matter
+    let meta: FrontMatter = fm
+        .map(|y| serde_yaml::from_str::<FrontMatter>(y))
+        .transpose()
+        .map_err(|e| format!("bad front matter: {e}"))?
+        .unwrap_or_default();
+    Ok((meta, body))
+}
+
+pub(super) fn split_front_matter(md: &str) -> Result<(Option<&str>, &str), String> {
     let trimmed = md.trim_start();
     if !trimmed.starts_with("---\n") && !trimmed.starts_with("---\r\n") {
         // No front matter, just return the markdown
         return Ok((None, md));
     }
-    
+
     // Find closing tag
     let rest = &trimmed[4..];
     if let Some(end) = rest.find("\n---") {
@@ -153,37 +442,75 @@ fn split_front_matter(md: &str) -> Result<(Option<&str>, &str), String> {
     }
 }
 
-fn rewrite_wikilinks(s: &str) -> String {
+/// Removes the `password` key from the front matter, so the plain text password is never stored
+/// with the post source. Front matter with a password is parsed and written back out, so every
+/// YAML form of the key is caught; other front matter is left as written.
+/// # Returns
+/// - `Result<String, String>` - Markdown without the password, or an error if the password
+///   could not be removed
+pub(super) fn redact_password(md: &str) -> Result<String, String> {
+    let (meta, _) = parse_front_matter(md)?;
+    let Some(fm) = split_front_matter(md)?.0.filter(|_| meta.password.is_some()) else {
+        return Ok(md.to_string());
+    };
+
+    let mut yaml: serde_yaml::Mapping =
+        serde_yaml::from_str(fm).map_err(|e| format!("bad front matter: {e}"))?;
+    yaml.remove("password");
+    let yaml = serde_yaml::to_string(&yaml).map_err(|e| format!("bad front matter: {e}"))?;
+    // The front matter is a slice of `md`
+    let start = fm.as_ptr() as usize - md.as_ptr() as usize;
+    let redacted = format!("{}{}{}", &md[..start], yaml.trim_end(), &md[start + fm.len()..]);
+
+    // Merge keys and the like could still bring it back
+    if parse_front_matter(&redacted)?.0.password.is_some() {
+        return Err("the front matter password could not be removed".into());
+    }
+    Ok(redacted)
+}
+
+/// Rewrites `[[target|text]]` wikilinks into markdown links
+/// # Returns
+/// - `(String, Vec<String>)` - Rewritten markdown and the slugs of every linked post
+fn rewrite_wikilinks(s: &str) -> (String, Vec<String>) {
     // TODO: Handle external links
     let re = Regex::new(r"\[\[([^\]\|]+)(?:\|([^\]]+))?\]\]").unwrap();
-    re.replace_all(s, |caps: &regex::Captures| {
+    let mut links = Vec::new();
+    let body = re.replace_all(s, |caps: &regex::Captures| {
         let target = caps.get(1).unwrap().as_str().trim();
         let text = caps.get(2).map(|m| m.as_str()).unwrap_or(target);
         let slug = slug::slugify(target);
-        format!("[{text}](/blog/{slug})")
-    }).into_owned()
+        let link = format!("[{text}](/blog/{slug})");
+        if !links.contains(&slug) {
+            links.push(slug);
+        }
+        link
+    }).into_owned();
+    (body, links)
 }
 
 fn md_to_html(s: &str) -> String {
-    let options = ComrakOptions {
-        extension: ComrakExtensionOptions {
-            strikethrough: true,
-            table: true,
-            autolink: true,
-            tasklist: true,
-            footnotes: true,
-            ..Default::default()
-        },
-        parse: ComrakParseOptions { smart: true, ..Default::default() },
-        render: ComrakRenderOptions { hardbreaks: false, ..Default::default() },
-    };
-    markdown_to_html(r, &options)
+    markdown_to_html(s, &comrak_options())
+}
+
+/// Markdown options for posts. Shared with the linter so it sees the same document.
+pub(super) fn comrak_options() -> ComrakOptions<'static> {
+    let mut options = ComrakOptions::default();
+    options.extension.strikethrough = true;
+    options.extension.table = true;
+    options.extension.autolink = true;
+    options.extension.tasklist = true;
+    options.extension.footnotes = true;
+    options.parse.smart = true;
+    options.render.hardbreaks = false;
+    options
 }
 
 fn sanitize(html: &str) -> String {
     HtmlSanitizer::default()
+        .add_tags(&["mark"])
         .link_rel(None)
-        clean(html)
+        .clean(html)
         .to_string()
 }
 
@@ -194,13 +521,13 @@ fn infer_title(md: &str, filename: &str) -> Option<String> {
         if line.starts_with("# ") {
             return Some(line.trim_start_matches("# ").trim().to_string());
         }
-        if !line.is_empty() { 
+        if !line.is_empty() {
             // Trim extension from filename
             let filename = Path::new(filename)
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .unwrap_or(filename);
-            return Some(filename.into()); 
+            return Some(filename.into());
         }
     }
     None
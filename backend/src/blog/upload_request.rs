@@ -0,0 +1,18 @@
+use super::post_status::PostStatus;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Deserialize;
+
+/// Represents a markdown upload sent as JSON
+#[derive(JsonSchema, Deserialize)]
+pub struct UploadRequest {
+    /// Markdown source, including front matter
+    pub markdown: String,
+    /// Original file name, used to infer a missing title
+    pub filename: Option<String>,
+    /// Status to create the post with. Defaults to `scheduled` if the front matter has a future
+    /// `publish_date`, `draft` otherwise. Ignored when editing a post.
+    pub status: Option<PostStatus>,
+    /// Refuse the upload if there are any lint warnings
+    #[serde(default)]
+    pub strict: bool,
+}
@@ -0,0 +1,50 @@
+use super::lint_warning::LintWarning;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents the result of a post upload
+#[derive(Serialize, JsonSchema)]
+pub struct UploadResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Lint warnings. When the upload is `strict` and there are warnings, nothing is saved.
+    pub warnings: Vec<LintWarning>,
+}
+
+impl UploadResponse {
+    pub fn ok(post_id: i32, slug: String, warnings: Vec<LintWarning>) -> Self {
+        Self {
+            ok: true,
+            post_id: Some(post_id),
+            slug: Some(slug),
+            error: None,
+            warnings,
+        }
+    }
+
+    pub fn error(e: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            post_id: None,
+            slug: None,
+            error: Some(e.into()),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Strict upload refused because of lint warnings
+    pub fn rejected(warnings: Vec<LintWarning>) -> Self {
+        Self {
+            ok: false,
+            post_id: None,
+            slug: None,
+            error: Some("upload has lint warnings and strict is set".into()),
+            warnings,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use super::post_visibility::PostVisibility;
+use crate::auth::Roles;
+use rocket::http::Status;
+
+/// SQL condition matching the posts a viewer may find in lists, for the post table alias
+/// `alias`. The parameter `$param` must be bound with `met_roles`.
+pub(super) fn listed(alias: &str, param: usize) -> String {
+    format!(
+        "({alias}.visibility = 'public' \
+        OR ({alias}.visibility = 'members' AND {alias}.min_role = ANY(${param})))"
+    )
+}
+
+/// Returns the minimum roles a viewer satisfies, for binding to a `listed` condition
+/// # Arguments
+/// - `viewer`: `Option<Roles>` - Role of the signed-in user, `None` for anonymous readers
+pub(super) fn met_roles(viewer: Option<Roles>) -> Vec<Roles> {
+    let Some(viewer) = viewer else {
+        return Vec::new();
+    };
+    [Roles::Guest, Roles::Author, Roles::Admin]
+        .into_iter()
+        .filter(|role| viewer.authorize(*role).is_ok())
+        .collect()
+}
+
+/// Returns `Ok` if a viewer may read a post by direct link
+/// # Arguments
+/// - `visibility`: `PostVisibility` - Visibility of the post
+/// - `min_role`: `Roles` - Minimum role for members-only posts
+/// - `viewer`: `Option<Roles>` - Role of the signed-in user, `None` for anonymous readers
+pub(super) fn authorize_read(
+    visibility: PostVisibility,
+    min_role: Roles,
+    viewer: Option<Roles>,
+) -> Result<(), Status> {
+    match visibility {
+        PostVisibility::Public | PostVisibility::Unlisted => Ok(()),
+        PostVisibility::Members => viewer.ok_or(Status::Unauthorized)?.authorize(min_role),
+    }
+}
@@ -0,0 +1,49 @@
+use super::comment_status::CommentStatus;
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use rocket_db_pools::sqlx::{Row, postgres::PgRow};
+use serde::Serialize;
+
+/// Represents a comment on a post. Comments are listed depth-first, so each reply follows its
+/// parent.
+#[derive(Serialize, JsonSchema)]
+pub struct Comment {
+    pub id: i32,
+    pub post_id: i32,
+    /// The comment this replies to, `None` for top-level comments
+    pub parent_id: Option<i32>,
+    /// Number of ancestors above this comment
+    pub depth: i32,
+    /// `None` if the commenter's account was deleted
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+    /// Markdown source. `"[deleted]"` for deleted comments that still have replies.
+    pub body: String,
+    /// Rendered, sanitized HTML
+    pub html: String,
+    pub status: CommentStatus,
+    pub deleted: bool,
+    pub created_at: DateTime<Utc>,
+    /// Set when the comment has been edited
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    /// Reads a comment from a `get_comments` row
+    pub(super) fn from_row(r: &PgRow) -> Self {
+        Self {
+            id: r.get("id"),
+            post_id: r.get("post_id"),
+            parent_id: r.get("parent_id"),
+            depth: r.get("depth"),
+            user_id: r.get("user_id"),
+            username: r.get("username"),
+            body: r.get("body"),
+            html: r.get("body_html"),
+            status: r.get("status"),
+            deleted: r.get("deleted"),
+            created_at: r.get("created_at"),
+            updated_at: r.get("updated_at"),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Deserialize;
+
+/// Represents a new or edited comment
+#[derive(Deserialize, JsonSchema)]
+pub struct CommentRequest {
+    pub body: String,
+    /// Comment to reply to. Ignored when editing.
+    pub parent_id: Option<i32>,
+}
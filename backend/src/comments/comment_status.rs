@@ -0,0 +1,19 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+/// Moderation status of a comment
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema,
+    FromFormField,
+)]
+#[sqlx(type_name = "comment_status")] // Must match Postgres enum name
+#[sqlx(rename_all = "lowercase")] // Must match Postgres variant case
+#[serde(rename_all = "lowercase")]
+pub enum CommentStatus {
+    /// Waiting for a moderator. Only visible to its author.
+    #[default]
+    Pending,
+    Approved,
+    Spam,
+    Rejected,
+}
@@ -0,0 +1,250 @@
+use super::comment::Comment;
+use super::comment_request::CommentRequest;
+use super::comment_status::CommentStatus;
+use super::moderation::initial_status;
+use super::render::render_comment;
+use crate::auth::{self, AuthUser, Roles};
+use crate::blog;
+use crate::config::config;
+use crate::db::{BlogDB, map_db_err};
+use rocket::{
+    http::{CookieJar, Status},
+    serde::json::Json,
+};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+use sqlx::Connection as _;
+
+/// Longest comment accepted, in characters
+const MAX_COMMENT_LENGTH: usize = 10_000;
+
+/// Selects comments in the shape `Comment::from_row` reads, for queries on `comments AS c`
+pub(super) const SELECT_COMMENTS: &str = "SELECT c.id, c.post_id, c.parent_id, \
+    comment_depth(c.id) AS depth, c.user_id, u.username, c.body, c.body_html, c.status, \
+    c.deleted, c.created_at, c.updated_at \
+    FROM comments AS c LEFT JOIN users AS u ON c.user_id = u.id";
+
+/// Whether comment `c` has replies that `get_comments` may show. Hidden replies don't keep a
+/// deleted comment's thread together, since nobody sees it.
+const HAS_VISIBLE_REPLIES: &str = "EXISTS(SELECT 1 FROM comments AS r \
+    WHERE r.parent_id = c.id AND r.status IN ('approved', 'pending'))";
+
+/// Lists the approved comments on a post as a flat, depth-first thread. Signed in users also
+/// see their own comments awaiting moderation. Password-protected posts must be unlocked first.
+#[openapi]
+#[get("/blog/<slug>/comments")]
+pub async fn list_comments(
+    slug: String,
+    user: Option<auth::RequireRole<auth::Guest>>,
+    mut db: Connection<BlogDB>,
+    jar: &CookieJar<'_>,
+) -> Result<Json<Vec<Comment>>, Status> {
+    let post_id = blog::readable_post_id(&mut db, &slug, user.as_ref().map(|u| u.role())).await?;
+    blog::require_unlocked(&mut db, jar, post_id).await?;
+
+    let comments = sqlx::query("SELECT * FROM get_comments($1, $2)")
+        .bind(post_id)
+        .bind(user.map(|u| u.id()))
+        .fetch_all(&mut **db)
+        .await
+        .map_err(map_db_err)?
+        .iter()
+        .map(Comment::from_row)
+        .collect();
+
+    Ok(Json(comments))
+}
+
+/// Adds a comment to a post, or a reply to another comment on it. Depending on the moderation
+/// mode, the comment may be held until an admin approves it. Requires a solved challenge from
+/// `/challenge`, and password-protected posts must be unlocked first.
+#[openapi]
+#[post("/blog/<slug>/comments", format = "json", data = "<req>")]
+pub async fn create_comment(
+    slug: String,
+    user: AuthUser,
+    _pow: auth::ProofOfWork,
+    mut db: Connection<BlogDB>,
+    jar: &CookieJar<'_>,
+    req: Json<CommentRequest>,
+) -> Result<Json<Comment>, Status> {
+    let body = validate_body(&req.body)?;
+    let role: Roles = sqlx::query_scalar("SELECT role FROM users WHERE id = $1")
+        .bind(user.0)
+        .fetch_one(&mut **db)
+        .await
+        .map_err(map_db_err)?;
+    let post_id = blog::readable_post_id(&mut db, &slug, Some(role)).await?;
+    blog::require_unlocked(&mut db, jar, post_id).await?;
+
+    if let Some(parent_id) = req.parent_id {
+        let parent = sqlx::query(
+            "SELECT post_id, user_id, status, deleted, comment_depth(id) AS depth \
+            FROM comments WHERE id = $1",
+        )
+        .bind(parent_id)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(map_db_err)?
+        .ok_or(Status::BadRequest)?;
+        // Only comments the user can see may be replied to
+        let parent_status: CommentStatus = parent.get("status");
+        let parent_owner: Option<i32> = parent.get("user_id");
+        let visible = parent_status == CommentStatus::Approved
+            || (parent_status == CommentStatus::Pending && parent_owner == Some(user.0));
+        if !visible {
+            return Err(Status::BadRequest);
+        }
+        // Replies must stay on the parent's post and within the thread depth limit
+        let parent_post: i32 = parent.get("post_id");
+        let parent_depth: i32 = parent.get("depth");
+        if parent_post != post_id || parent_depth >= config().comments.depth {
+            return Err(Status::BadRequest);
+        }
+        if parent.get::<bool, _>("deleted") {
+            return Err(Status::Conflict);
+        }
+    }
+
+    let status = initial_status(&mut db, user.0, role).await?;
+    let id: i32 = sqlx::query_scalar(
+        "INSERT INTO comments (user_id, post_id, parent_id, body, body_html, status) \
+        VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+    )
+    .bind(user.0)
+    .bind(post_id)
+    .bind(req.parent_id)
+    .bind(body)
+    .bind(render_comment(body))
+    .bind(status)
+    .fetch_one(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+
+    load_comment(&mut db, id).await.map(Json)
+}
+
+/// Edits one of your own comments. The edit is moderated like a new comment, and comments
+/// rejected or marked as spam can't be edited.
+#[openapi]
+#[put("/comments/<id>", format = "json", data = "<req>")]
+pub async fn edit_comment(
+    id: i32,
+    user: AuthUser,
+    mut db: Connection<BlogDB>,
+    req: Json<CommentRequest>,
+) -> Result<Json<Comment>, Status> {
+    let body = validate_body(&req.body)?;
+    // Deleted comments have no owner, so they can't be edited
+    let row = sqlx::query(
+        "SELECT c.user_id, c.status, u.role FROM comments AS c \
+        LEFT JOIN users AS u ON c.user_id = u.id WHERE c.id = $1",
+    )
+    .bind(id)
+    .fetch_one(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+    let owner: Option<i32> = row.get("user_id");
+    let current: CommentStatus = row.get("status");
+    if owner != Some(user.0) || matches!(current, CommentStatus::Spam | CommentStatus::Rejected) {
+        return Err(Status::Forbidden);
+    }
+
+    let status = initial_status(&mut db, user.0, row.get("role")).await?;
+    sqlx::query(
+        "UPDATE comments SET body = $1, body_html = $2, status = $3, updated_at = NOW() \
+        WHERE id = $4",
+    )
+    .bind(body)
+    .bind(render_comment(body))
+    .bind(status)
+    .bind(id)
+    .execute(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+
+    load_comment(&mut db, id).await.map(Json)
+}
+
+/// Deletes a comment. Users may delete their own comments, admins any comment.
+/// A comment with replies is replaced by a "[deleted]" placeholder so its thread stays intact.
+#[openapi]
+#[delete("/comments/<id>")]
+pub async fn delete_comment(
+    id: i32,
+    user: auth::RequireRole<auth::Guest>,
+    mut db: Connection<BlogDB>,
+) -> Result<(), Status> {
+    // The row lock holds off new replies until the comment is gone or replaced
+    let mut tx = db.begin().await.map_err(map_db_err)?;
+    let row = sqlx::query(&format!(
+        "SELECT user_id, {HAS_VISIBLE_REPLIES} AS replies FROM comments AS c WHERE c.id = $1 \
+        FOR UPDATE"
+    ))
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(map_db_err)?;
+    let owner: Option<i32> = row.get("user_id");
+    if owner != Some(user.id()) && user.role().authorize(Roles::Admin).is_err() {
+        return Err(Status::Forbidden);
+    }
+
+    if row.get::<bool, _>("replies") {
+        // Approved, so the placeholder shows even if the comment was still pending
+        sqlx::query(
+            "UPDATE comments SET deleted = true, user_id = NULL, body = '', body_html = '', \
+            status = 'approved' WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(map_db_err)?;
+        return tx.commit().await.map_err(map_db_err);
+    }
+
+    let mut parent: Option<i32> =
+        sqlx::query_scalar("DELETE FROM comments WHERE id = $1 RETURNING parent_id")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(map_db_err)?;
+
+    // Placeholders left without replies have nothing to hold together any more
+    while let Some(parent_id) = parent {
+        parent = sqlx::query_scalar(&format!(
+            "DELETE FROM comments AS c WHERE c.id = $1 AND c.deleted \
+            AND NOT {HAS_VISIBLE_REPLIES} RETURNING c.parent_id"
+        ))
+        .bind(parent_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_db_err)?
+        .flatten();
+    }
+
+    tx.commit().await.map_err(map_db_err)
+}
+
+/// Loads a single comment as it appears in `get_comments`
+/// # Arguments
+/// - `db`: `&mut Connection<BlogDB>` - Rocket Sqlx_pools DB
+/// - `id`: `i32` - Comment to load
+async fn load_comment(db: &mut Connection<BlogDB>, id: i32) -> Result<Comment, Status> {
+    let row = sqlx::query(&format!("{SELECT_COMMENTS} WHERE c.id = $1"))
+        .bind(id)
+        .fetch_one(&mut ***db)
+        .await
+        .map_err(map_db_err)?;
+
+    Ok(Comment::from_row(&row))
+}
+
+/// Trims a comment body, refusing empty and overlong comments
+fn validate_body(body: &str) -> Result<&str, Status> {
+    let body = body.trim();
+    if body.is_empty() || body.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(Status::BadRequest);
+    }
+    Ok(body)
+}
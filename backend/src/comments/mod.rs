@@ -0,0 +1,14 @@
+mod comment;
+mod comment_request;
+mod comment_status;
+mod endpoints;
+mod moderation;
+mod moderation_request;
+mod render;
+
+pub use comment::Comment;
+pub use comment_request::CommentRequest;
+pub use comment_status::CommentStatus;
+pub use endpoints::*;
+pub use moderation::*;
+pub use moderation_request::ModerationRequest;
@@ -0,0 +1,86 @@
+use super::comment::Comment;
+use super::comment_status::CommentStatus;
+use super::endpoints::SELECT_COMMENTS;
+use super::moderation_request::ModerationRequest;
+use crate::auth::{self, Roles};
+use crate::config::{Moderation, config};
+use crate::db::{BlogDB, map_db_err};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::Connection;
+use rocket_okapi::openapi;
+
+/// Lists comments with a moderation status, oldest first. Lists the pending queue by default.
+#[openapi]
+#[get("/admin/comments?<status>")]
+pub async fn moderation_queue(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    status: Option<CommentStatus>,
+) -> Result<Json<Vec<Comment>>, Status> {
+    let comments = sqlx::query(&format!(
+        "{SELECT_COMMENTS} WHERE c.status = $1 AND NOT c.deleted ORDER BY c.created_at, c.id"
+    ))
+    .bind(status.unwrap_or_default())
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .iter()
+    .map(Comment::from_row)
+    .collect();
+
+    Ok(Json(comments))
+}
+
+/// Approves, rejects or marks as spam several comments at once. Returns the IDs of the
+/// comments that were found and updated; deleted placeholders are skipped.
+#[openapi]
+#[put("/admin/comments/status", format = "json", data = "<req>")]
+pub async fn moderate_comments(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+    req: Json<ModerationRequest>,
+) -> Result<Json<Vec<i32>>, Status> {
+    if req.status == CommentStatus::Pending {
+        return Err(Status::BadRequest);
+    }
+
+    let ids: Vec<i32> = sqlx::query_scalar(
+        "UPDATE comments SET status = $1 WHERE id = ANY($2) AND NOT deleted RETURNING id",
+    )
+    .bind(req.status)
+    .bind(&req.ids)
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(Json(ids))
+}
+
+/// Decides whether a new or edited comment is held for moderation, following the configured
+/// moderation mode
+/// # Arguments
+/// - `db`: `&mut Connection<BlogDB>` - Rocket Sqlx_pools DB
+/// - `user_id`: `i32` - Commenter's user ID
+/// - `role`: `auth::Roles` - Commenter's role
+/// # Returns
+/// - `Result<CommentStatus, Status>` - `Approved` or `Pending`
+pub(super) async fn initial_status(
+    db: &mut Connection<BlogDB>,
+    user_id: i32,
+    role: Roles,
+) -> Result<CommentStatus, Status> {
+    let approved = match config().comments.moderation {
+        Moderation::All => false,
+        Moderation::Authors => role.authorize(Roles::Author).is_ok(),
+        Moderation::First if role == Roles::Admin => true,
+        Moderation::First => sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM comments WHERE user_id = $1 AND status = 'approved')",
+        )
+        .bind(user_id)
+        .fetch_one(&mut ***db)
+        .await
+        .map_err(map_db_err)?,
+    };
+
+    Ok(if approved { CommentStatus::Approved } else { CommentStatus::Pending })
+}
@@ -0,0 +1,11 @@
+use super::comment_status::CommentStatus;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Deserialize;
+
+/// Represents a request to moderate several comments at once
+#[derive(Deserialize, JsonSchema)]
+pub struct ModerationRequest {
+    pub ids: Vec<i32>,
+    /// `approved`, `rejected` or `spam`
+    pub status: CommentStatus,
+}
@@ -0,0 +1,60 @@
+use ammonia::{Builder as HtmlSanitizer, UrlRelative};
+use comrak::{Arena, Options as ComrakOptions, format_html, nodes::NodeValue, parse_document};
+use std::collections::HashSet;
+
+/// Tags a rendered comment may contain. Lists and anything else outside the comment subset are
+/// stripped down to their text.
+const COMMENT_TAGS: [&str; 9] = [
+    "p", "br", "em", "strong", "del", "code", "pre", "blockquote", "a",
+];
+
+/// Renders a comment's markdown to sanitized HTML. Only emphasis, code, links and quotes
+/// survive: headings become paragraphs, images become links to the image, and raw HTML is
+/// escaped.
+/// # Arguments
+/// - `md`: `&str` - Markdown source of the comment
+pub(super) fn render_comment(md: &str) -> String {
+    let arena = Arena::new();
+    let options = comrak_options();
+    let root = parse_document(&arena, md, &options);
+
+    for node in root.descendants() {
+        let mut ast = node.data.borrow_mut();
+        let replacement = match &ast.value {
+            NodeValue::Heading(_) => NodeValue::Paragraph,
+            NodeValue::Image(image) => NodeValue::Link(image.clone()),
+            _ => continue,
+        };
+        ast.value = replacement;
+    }
+
+    let mut html = Vec::new();
+    if format_html(root, &options, &mut html).is_err() {
+        return String::new();
+    }
+    sanitize(&String::from_utf8_lossy(&html))
+}
+
+/// Markdown options for comments. Much narrower than the post options.
+fn comrak_options() -> ComrakOptions<'static> {
+    let mut options = ComrakOptions::default();
+    options.extension.strikethrough = true;
+    options.extension.autolink = true;
+    options.render.hardbreaks = true;
+    // Raw HTML is shown as text rather than dropped, so nothing typed silently disappears
+    options.render.escape = true;
+    options
+}
+
+/// Cleans rendered comment HTML. Unlike posts, only a handful of tags and absolute links are
+/// kept, and links are marked as user-generated so they pass on no ranking.
+fn sanitize(html: &str) -> String {
+    HtmlSanitizer::empty()
+        .add_tags(COMMENT_TAGS)
+        .add_tag_attributes("a", ["href"])
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .url_relative(UrlRelative::Deny)
+        .link_rel(Some("nofollow ugc"))
+        .clean(html)
+        .to_string()
+}
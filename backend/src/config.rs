@@ -1,3 +1,4 @@
+use chrono_tz::Tz;
 use figment::{Figment, providers::Env};
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
@@ -59,11 +60,125 @@ fn connect_timeout() -> u64 { 3 }
 fn idle_timeout() -> Option<u64> { None }
 fn extensions() -> Option<Vec<String>> { None }
 
+/// Public site data
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct SiteConfig {
+    /// Public base URL, without a trailing slash. Used for canonical URLs.
+    #[serde(default = "site_url")]
+    pub url: String,
+    #[serde(default = "site_name")]
+    pub name: String,
+    /// IANA time zone name, e.g. `Europe/Berlin`. Front matter dates without an offset are read
+    /// in it.
+    #[serde(default = "site_timezone")]
+    pub timezone: Tz,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            url: site_url(),
+            name: site_name(),
+            timezone: site_timezone(),
+        }
+    }
+}
+
+/// Media store data
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct MediaConfig {
+    /// Directory media files are stored in and served from
+    #[serde(default = "media_path")]
+    pub path: String,
+    /// TrueType/OpenType font used to draw social cards. No cards are generated without one.
+    #[serde(default)]
+    pub font: Option<String>,
+}
+
+impl Default for MediaConfig {
+    fn default() -> Self {
+        Self {
+            path: media_path(),
+            font: None,
+        }
+    }
+}
+
+/// Comment thread data
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct CommentsConfig {
+    /// Deepest reply nesting allowed. Top-level comments are depth 0.
+    #[serde(default = "comments_depth")]
+    pub depth: i32,
+    #[serde(default)]
+    pub moderation: Moderation,
+}
+
+impl Default for CommentsConfig {
+    fn default() -> Self {
+        Self {
+            depth: comments_depth(),
+            moderation: Moderation::default(),
+        }
+    }
+}
+
+/// Which new comments are held for moderation
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Moderation {
+    /// Hold every comment, admins' included
+    All,
+    /// Hold comments from users without an approved comment yet. Admins are never held.
+    #[default]
+    First,
+    /// Approve comments from authors and admins, hold everyone else's
+    Authors,
+}
+
+/// Proof-of-work challenge data
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct PowConfig {
+    /// Leading zero bits a solution's hash needs. Each extra bit doubles the expected work.
+    #[serde(default = "pow_difficulty")]
+    pub difficulty: u32,
+}
+
+impl Default for PowConfig {
+    fn default() -> Self {
+        Self {
+            difficulty: pow_difficulty(),
+        }
+    }
+}
+
+// SiteConfig defaults
+fn site_url() -> String { "http://localhost:8000".into() }
+fn site_name() -> String { "Blog".into() }
+fn site_timezone() -> Tz { Tz::UTC }
+
+// MediaConfig defaults
+fn media_path() -> String { "media".into() }
+
+// CommentsConfig defaults
+fn comments_depth() -> i32 { 4 }
+
+// PowConfig defaults
+fn pow_difficulty() -> u32 { 18 }
+
 /// Server configuration data
 #[derive(Clone, PartialEq, Deserialize)]
 pub struct ServerConfig {
     pub blog: DBConfig,
     pub secret: String,
+    #[serde(default)]
+    pub site: SiteConfig,
+    #[serde(default)]
+    pub media: MediaConfig,
+    #[serde(default)]
+    pub comments: CommentsConfig,
+    #[serde(default)]
+    pub pow: PowConfig,
 }
 
 /// Returns the server configuration data.
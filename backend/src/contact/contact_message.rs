@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Serialize;
+
+/// Represents a message received through the contact form
+#[derive(Serialize, JsonSchema)]
+pub struct ContactMessage {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,11 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::Deserialize;
+
+/// Represents a message sent through the contact form
+#[derive(Deserialize, JsonSchema)]
+pub struct ContactRequest {
+    pub name: String,
+    /// Address to answer to
+    pub email: String,
+    pub message: String,
+}
@@ -0,0 +1,70 @@
+use super::contact_message::ContactMessage;
+use super::contact_request::ContactRequest;
+use crate::auth;
+use crate::db::{BlogDB, map_db_err};
+use rocket::{http::Status, serde::json::Json};
+use rocket_db_pools::{Connection, sqlx::Row};
+use rocket_okapi::openapi;
+
+/// Longest message accepted, in characters
+const MAX_MESSAGE_LENGTH: usize = 10_000;
+
+/// Longest name or email address accepted, in characters
+const MAX_FIELD_LENGTH: usize = 200;
+
+/// Stores a message from the contact form. Requires a solved challenge from `/challenge`.
+#[openapi]
+#[post("/contact", format = "json", data = "<req>")]
+pub async fn send_message(
+    _pow: auth::ProofOfWork,
+    mut db: Connection<BlogDB>,
+    req: Json<ContactRequest>,
+) -> Result<(), Status> {
+    let (name, email, message) = (req.name.trim(), req.email.trim(), req.message.trim());
+    if name.is_empty() || name.chars().count() > MAX_FIELD_LENGTH {
+        return Err(Status::BadRequest);
+    }
+    if !email.contains('@') || email.chars().count() > MAX_FIELD_LENGTH {
+        return Err(Status::BadRequest);
+    }
+    if message.is_empty() || message.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(Status::BadRequest);
+    }
+
+    sqlx::query("INSERT INTO contact_message (name, email, message) VALUES ($1, $2, $3)")
+        .bind(name)
+        .bind(email)
+        .bind(message)
+        .execute(&mut **db)
+        .await
+        .map_err(map_db_err)?;
+
+    Ok(())
+}
+
+/// Lists messages from the contact form, newest first
+#[openapi]
+#[get("/admin/contact")]
+pub async fn list_messages(
+    _user: auth::RequireRole<auth::Admin>,
+    mut db: Connection<BlogDB>,
+) -> Result<Json<Vec<ContactMessage>>, Status> {
+    let messages = sqlx::query(
+        "SELECT id, name, email, message, created_at FROM contact_message \
+        ORDER BY created_at DESC, id DESC",
+    )
+    .fetch_all(&mut **db)
+    .await
+    .map_err(map_db_err)?
+    .into_iter()
+    .map(|r| ContactMessage {
+        id: r.get("id"),
+        name: r.get("name"),
+        email: r.get("email"),
+        message: r.get("message"),
+        created_at: r.get("created_at"),
+    })
+    .collect();
+
+    Ok(Json(messages))
+}
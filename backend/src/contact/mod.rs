@@ -0,0 +1,7 @@
+mod contact_message;
+mod contact_request;
+mod endpoints;
+
+pub use contact_message::ContactMessage;
+pub use contact_request::ContactRequest;
+pub use endpoints::*;
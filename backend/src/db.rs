@@ -1,5 +1,30 @@
+use rocket::http::Status;
 use rocket_db_pools::Database;
 
 #[derive(Database)]
 #[database("blog")]
 pub struct BlogDB(rocket_db_pools::sqlx::PgPool);
+
+/// Maps a database error to the closest HTTP status
+pub fn map_db_err(e: sqlx::Error) -> Status {
+    use std::io::ErrorKind;
+
+    match &e {
+        // No matching row for SELECT … FETCH_ONE
+        sqlx::Error::RowNotFound => Status::NotFound,
+
+        // Couldn’t reach the DB / pool timed out
+        sqlx::Error::Io(io) => {
+            if io.kind() == ErrorKind::TimedOut {
+                Status::ServiceUnavailable
+            } else {
+                Status::InternalServerError
+            }
+        }
+        sqlx::Error::PoolTimedOut => Status::ServiceUnavailable,
+        sqlx::Error::PoolClosed => Status::ServiceUnavailable,
+
+        // Anything else
+        _ => Status::InternalServerError,
+    }
+}
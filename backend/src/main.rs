@@ -1,4 +1,4 @@
-use rocket::{get, serde::json::Json};
+use rocket::{fs::FileServer, get, serde::json::Json};
 use rocket_db_pools::{Connection, Database};
 use rocket_okapi::{
     okapi::{schemars, schemars::JsonSchema},
@@ -22,6 +22,9 @@ mod db;
 use db::{BlogDB};
 mod routes;
 mod blog;
+mod comments;
+mod contact;
+mod media;
 
 fn ui() -> SwaggerUIConfig {
     SwaggerUIConfig {
@@ -49,9 +52,15 @@ fn rocket() -> _ {
     // Built server routes
     rocket::custom(rocket_config)
         .attach(BlogDB::init())
+        .manage(blog::PublishEvents::new())
+        .attach(blog::scheduler())
         .mount(
             "/", // openapi_get_routes![list_test_entries, auth::login, auth::signup],
             routes::get_routes(),
         )
         .mount("/docs", make_swagger_ui(&ui()))
+        .mount(
+            media::MEDIA_ROUTE,
+            FileServer::from(media::media_dir().expect("could not create media directory")),
+        )
 }
@@ -0,0 +1,54 @@
+use crate::config::config;
+use sha2::{Digest, Sha256};
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// URL path the media directory is served under
+pub const MEDIA_ROUTE: &str = "/media";
+
+/// Numbers temporary files, so concurrent renders never write to the same one
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the media directory, creating it if needed
+pub fn media_dir() -> io::Result<PathBuf> {
+    let dir = PathBuf::from(config().media.path);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Returns the media file for `key`, rendering and storing it first if it is not cached. Files
+/// are named by the SHA-256 hash of `key`, so the same content is only ever rendered once.
+/// # Arguments
+/// - `key`: `&[u8]` - Everything the file's content depends on
+/// - `ext`: `&str` - File extension, without the dot
+/// - `render`: `impl FnOnce() -> Option<Vec<u8>>` - Produces the file content
+/// # Returns
+/// - `io::Result<Option<String>>` - URL path of the file, or `None` if `render` gave nothing
+pub fn cached(
+    key: &[u8],
+    ext: &str,
+    render: impl FnOnce() -> Option<Vec<u8>>,
+) -> io::Result<Option<String>> {
+    let name = format!("{:x}.{ext}", Sha256::digest(key));
+    let path = media_dir()?.join(&name);
+
+    if !path.exists() {
+        let Some(bytes) = render() else {
+            return Ok(None);
+        };
+        // Write next to the final file and move it into place, so readers never see a partial
+        // file. Dot files are not served.
+        let n = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let temp = path.with_file_name(format!(".{name}.{}.{n}.tmp", std::process::id()));
+        fs::write(&temp, bytes)?;
+        if let Err(e) = fs::rename(&temp, &path) {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    }
+
+    Ok(Some(format!("{MEDIA_ROUTE}/{name}")))
+}
@@ -1,5 +1,7 @@
 use crate::auth;
 use crate::blog;
+use crate::comments;
+use crate::contact;
 use rocket::{get, serde::json::Json};
 use rocket_okapi::{
     okapi::{schemars, schemars::JsonSchema},
@@ -12,11 +14,47 @@ pub fn get_routes() -> Vec<rocket::Route> {
         auth::login,
         auth::logout,
         auth::signup,
+        auth::challenge,
         auth::create_admin,
         auth::me,
         auth::links,
         auth::admin,
         blog::upload,
+        blog::upload_json,
+        blog::upload_markdown,
+        blog::edit_post,
+        blog::delete_post,
+        blog::set_status,
+        blog::status_history,
+        blog::create_preview,
+        blog::list_previews,
+        blog::revoke_preview,
+        blog::read_preview,
+        blog::author,
+        blog::read_post,
+        blog::unlock,
+        blog::post_meta,
+        blog::series_index,
+        blog::tag,
+        blog::backlinks,
+        blog::graph,
+        blog::list_queues,
+        blog::create_queue,
+        blog::edit_queue,
+        blog::pause_queue,
+        blog::resume_queue,
+        blog::queued_posts,
+        blog::reorder_queue,
+        blog::publish_next,
+        blog::calendar,
+        comments::list_comments,
+        comments::create_comment,
+        comments::edit_comment,
+        comments::delete_comment,
+        comments::moderation_queue,
+        comments::moderate_comments,
+        contact::send_message,
+        contact::list_messages,
     ]
 }
 
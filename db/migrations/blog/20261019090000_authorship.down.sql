@@ -0,0 +1,12 @@
+-- Functions
+DROP FUNCTION IF EXISTS get_author_posts(integer);
+
+-- Tables
+ALTER TABLE updated_post
+DROP CONSTRAINT updated_post_post_id_fkey,
+ADD FOREIGN KEY (post_id) REFERENCES post(id);
+
+DROP TABLE IF EXISTS post_author;
+
+ALTER TABLE post
+DROP COLUMN author_id;
@@ -0,0 +1,50 @@
+-- Tables
+ALTER TABLE post
+ADD author_id integer,
+ADD FOREIGN KEY (author_id) REFERENCES users(id)
+	ON UPDATE CASCADE
+	ON DELETE SET NULL;
+
+CREATE TABLE post_author (
+	post_id integer not null,
+	user_id integer not null,
+	PRIMARY KEY (post_id, user_id),
+	FOREIGN KEY (post_id) REFERENCES post(id)
+		ON UPDATE CASCADE
+		ON DELETE CASCADE,
+	FOREIGN KEY (user_id) REFERENCES users(id)
+		ON UPDATE CASCADE
+		ON DELETE CASCADE
+);
+
+-- Deleting a post should not be blocked by its update notification
+ALTER TABLE updated_post
+DROP CONSTRAINT updated_post_post_id_fkey,
+ADD FOREIGN KEY (post_id) REFERENCES post(id)
+	ON UPDATE CASCADE
+	ON DELETE CASCADE;
+
+-- Functions
+CREATE OR REPLACE FUNCTION get_author_posts(view_author integer)
+RETURNS TABLE(id integer,
+	slug text,
+	title text,
+	category text,
+	publish_date timestamp without time zone
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT p.id, p.slug, p.title, p.category, p.publish_date
+	FROM post AS p
+	WHERE p.published = true
+	AND (p.author_id = view_author
+		OR EXISTS (
+			SELECT 1
+			FROM post_author AS a
+			WHERE a.post_id = p.id
+			AND a.user_id = view_author
+		))
+	ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC;
+END;
+$$ LANGUAGE plpgsql;
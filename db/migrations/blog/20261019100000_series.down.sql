@@ -0,0 +1,9 @@
+-- Functions
+DROP FUNCTION IF EXISTS get_series_nav(integer);
+
+-- Tables
+ALTER TABLE post
+DROP COLUMN series_order,
+DROP COLUMN series_id;
+
+DROP TABLE IF EXISTS series;
@@ -0,0 +1,52 @@
+-- Tables
+CREATE TABLE series (
+	id serial,
+	slug text not null unique,
+	title text not null,
+	PRIMARY KEY (id)
+);
+
+ALTER TABLE post
+ADD series_id integer,
+ADD series_order integer,
+ADD FOREIGN KEY (series_id) REFERENCES series(id)
+	ON UPDATE CASCADE
+	ON DELETE SET NULL;
+
+-- Functions
+CREATE OR REPLACE FUNCTION get_series_nav(view_post integer)
+RETURNS TABLE(series_slug text,
+	series_title text,
+	position bigint,
+	total bigint,
+	prev_slug text,
+	prev_title text,
+	next_slug text,
+	next_title text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH parts AS (
+		SELECT p.id,
+			p.series_id,
+			ROW_NUMBER() OVER w AS part_position,
+			COUNT(*) OVER () AS part_total,
+			LAG(p.slug) OVER w AS part_prev_slug,
+			LAG(p.title) OVER w AS part_prev_title,
+			LEAD(p.slug) OVER w AS part_next_slug,
+			LEAD(p.title) OVER w AS part_next_title
+		FROM post AS p
+		WHERE p.published = true
+		AND p.series_id = (SELECT v.series_id FROM post AS v WHERE v.id = view_post)
+		WINDOW w AS (ORDER BY p.series_order, p.id)
+	)
+	SELECT s.slug, s.title,
+		parts.part_position, parts.part_total,
+		parts.part_prev_slug, parts.part_prev_title,
+		parts.part_next_slug, parts.part_next_title
+	FROM parts
+	JOIN series AS s ON s.id = parts.series_id
+	WHERE parts.id = view_post;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,12 @@
+-- Tables
+-- Targets are stored by slug so links to posts that don't exist yet are kept
+CREATE TABLE post_link (
+	source_id integer not null,
+	target_slug text not null,
+	PRIMARY KEY (source_id, target_slug),
+	FOREIGN KEY (source_id) REFERENCES post(id)
+		ON UPDATE CASCADE
+		ON DELETE CASCADE
+);
+
+CREATE INDEX post_link_target_slug ON post_link (target_slug);
@@ -0,0 +1,5 @@
+-- Tables
+DROP TABLE IF EXISTS post_embed;
+
+ALTER TABLE post
+DROP COLUMN source;
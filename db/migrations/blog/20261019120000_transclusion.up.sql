@@ -0,0 +1,15 @@
+-- Tables
+-- Markdown source is kept so embedding posts can be re-rendered
+ALTER TABLE post
+ADD source text;
+
+CREATE TABLE post_embed (
+	source_id integer not null,
+	target_slug text not null,
+	PRIMARY KEY (source_id, target_slug),
+	FOREIGN KEY (source_id) REFERENCES post(id)
+		ON UPDATE CASCADE
+		ON DELETE CASCADE
+);
+
+CREATE INDEX post_embed_target_slug ON post_embed (target_slug);
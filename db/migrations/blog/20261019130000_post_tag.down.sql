@@ -0,0 +1,2 @@
+-- Tables
+DROP TABLE IF EXISTS post_tag;
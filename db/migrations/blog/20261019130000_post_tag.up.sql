@@ -0,0 +1,12 @@
+-- Tables
+CREATE TABLE post_tag (
+	post_id integer not null,
+	tag text not null,
+	tag_slug text not null,
+	PRIMARY KEY (post_id, tag_slug),
+	FOREIGN KEY (post_id) REFERENCES post(id)
+		ON UPDATE CASCADE
+		ON DELETE CASCADE
+);
+
+CREATE INDEX post_tag_tag_slug ON post_tag (tag_slug);
@@ -0,0 +1,31 @@
+-- Functions
+DROP FUNCTION IF EXISTS get_author_posts(integer);
+CREATE FUNCTION get_author_posts(view_author integer)
+RETURNS TABLE(id integer,
+	slug text,
+	title text,
+	category text,
+	publish_date timestamp without time zone
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT p.id, p.slug, p.title, p.category, p.publish_date
+	FROM post AS p
+	WHERE p.published = true
+	AND (p.author_id = view_author
+		OR EXISTS (
+			SELECT 1
+			FROM post_author AS a
+			WHERE a.post_id = p.id
+			AND a.user_id = view_author
+		))
+	ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Tables
+ALTER TABLE post
+DROP COLUMN excerpt,
+DROP COLUMN reading_time,
+DROP COLUMN word_count;
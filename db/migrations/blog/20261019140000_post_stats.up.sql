@@ -0,0 +1,36 @@
+-- Tables
+ALTER TABLE post
+ADD word_count integer not null default 0,
+ADD reading_time integer not null default 0,
+ADD excerpt text not null default '';
+
+-- Functions
+-- Return type changes, so the function has to be recreated
+DROP FUNCTION IF EXISTS get_author_posts(integer);
+CREATE FUNCTION get_author_posts(view_author integer)
+RETURNS TABLE(id integer,
+	slug text,
+	title text,
+	category text,
+	publish_date timestamp without time zone,
+	word_count integer,
+	reading_time integer,
+	excerpt text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT p.id, p.slug, p.title, p.category, p.publish_date,
+		p.word_count, p.reading_time, p.excerpt
+	FROM post AS p
+	WHERE p.published = true
+	AND (p.author_id = view_author
+		OR EXISTS (
+			SELECT 1
+			FROM post_author AS a
+			WHERE a.post_id = p.id
+			AND a.user_id = view_author
+		))
+	ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC;
+END;
+$$ LANGUAGE plpgsql;
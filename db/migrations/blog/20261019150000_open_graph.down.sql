@@ -0,0 +1,6 @@
+-- Tables
+ALTER TABLE post
+DROP COLUMN social_card,
+DROP COLUMN canonical_url,
+DROP COLUMN cover_image,
+DROP COLUMN description;
@@ -0,0 +1,6 @@
+-- Tables
+ALTER TABLE post
+ADD description text,
+ADD cover_image text,
+ADD canonical_url text,
+ADD social_card text;
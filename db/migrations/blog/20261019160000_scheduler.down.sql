@@ -0,0 +1,62 @@
+-- Functions
+DROP FUNCTION IF EXISTS tick_publish_queue();
+DROP FUNCTION IF EXISTS publish_next_post();
+DROP FUNCTION IF EXISTS publish_due_posts();
+
+CREATE FUNCTION publish_next_post()
+RETURNS integer
+AS $$
+DECLARE updated_count integer := 0;
+BEGIN
+	WITH next AS (
+		SELECT id
+		FROM post
+		WHERE published = false
+		AND queued = true
+		ORDER BY upload_date
+		LIMIT 1
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post
+	SET published = true, queued = false, publish_date = NOW()
+	WHERE post.id IN (SELECT id FROM next);
+
+	GET DIAGNOSTICS updated_count = ROW_COUNT;
+	RETURN updated_count;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION tick_publish_queue()
+RETURNS integer
+AS $$
+DECLARE
+	v_id int;
+	v_interval interval;
+	published int := 0;
+BEGIN
+	WITH due AS (
+		SELECT id, run_interval
+		FROM publish_queue
+		WHERE active = true
+		AND next_run_at <= NOW()
+		ORDER BY next_run_at
+		LIMIT 1
+		-- Only run once per date
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE publish_queue q
+	SET next_run_at = q.next_run_at + d.run_interval
+	FROM due d
+	WHERE q.id = d.id
+	RETURNING q.id, d.run_interval
+	INTO v_id, v_interval;
+
+	IF v_id IS NULL THEN
+		RETURN 0;
+	END IF;
+
+	published := publish_next_post();
+
+	RETURN published;
+END;
+$$ LANGUAGE plpgsql;
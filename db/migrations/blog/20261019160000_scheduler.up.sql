@@ -0,0 +1,77 @@
+-- Functions
+-- Publishing functions return the posts they publish so the backend scheduler can emit events
+DROP FUNCTION IF EXISTS tick_publish_queue();
+DROP FUNCTION IF EXISTS publish_next_post();
+
+CREATE FUNCTION publish_due_posts()
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH due AS (
+		SELECT d.id
+		FROM post AS d
+		WHERE d.publish_date IS NOT NULL
+		AND d.publish_date <= NOW()
+		AND d.published = false
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post AS p
+	SET published = true, queued = false
+	WHERE p.id IN (SELECT due.id FROM due)
+	RETURNING p.id, p.slug;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION publish_next_post()
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH next AS (
+		SELECT n.id
+		FROM post AS n
+		WHERE n.published = false
+		AND n.queued = true
+		ORDER BY n.upload_date
+		LIMIT 1
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post AS p
+	SET published = true, queued = false, publish_date = NOW()
+	WHERE p.id IN (SELECT next.id FROM next)
+	RETURNING p.id, p.slug;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION tick_publish_queue()
+RETURNS TABLE(id integer, slug text)
+AS $$
+DECLARE
+	v_id int;
+BEGIN
+	WITH due AS (
+		SELECT pq.id, pq.run_interval
+		FROM publish_queue AS pq
+		WHERE pq.active = true
+		AND pq.next_run_at <= NOW()
+		ORDER BY pq.next_run_at
+		LIMIT 1
+		-- Only run once per date
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE publish_queue q
+	SET next_run_at = q.next_run_at + d.run_interval
+	FROM due d
+	WHERE q.id = d.id
+	RETURNING q.id
+	INTO v_id;
+
+	IF v_id IS NULL THEN
+		RETURN;
+	END IF;
+
+	RETURN QUERY
+	SELECT n.id, n.slug FROM publish_next_post() AS n;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,37 @@
+-- Functions
+CREATE OR REPLACE FUNCTION publish_next_post()
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH next AS (
+		SELECT n.id
+		FROM post AS n
+		WHERE n.published = false
+		AND n.queued = true
+		ORDER BY n.upload_date
+		LIMIT 1
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post AS p
+	SET published = true, queued = false, publish_date = NOW()
+	WHERE p.id IN (SELECT next.id FROM next)
+	RETURNING p.id, p.slug;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Views
+-- The view selects every column, so it has to go before the column does
+DROP VIEW IF EXISTS next_queued_post;
+
+-- Tables
+ALTER TABLE post DROP COLUMN IF EXISTS queue_position;
+
+CREATE VIEW next_queued_post
+AS
+SELECT *
+FROM post
+WHERE published = false
+AND queued = true
+ORDER BY upload_date
+LIMIT 1;
@@ -0,0 +1,47 @@
+-- Tables
+ALTER TABLE post ADD COLUMN queue_position integer;
+
+-- Number the existing queue by upload date
+UPDATE post AS p
+SET queue_position = o.position
+FROM (
+	SELECT id, ROW_NUMBER() OVER (ORDER BY upload_date) AS position
+	FROM post
+	WHERE published = false
+	AND queued = true
+) AS o
+WHERE p.id = o.id;
+
+-- Views
+DROP VIEW IF EXISTS next_queued_post;
+
+CREATE VIEW next_queued_post
+AS
+SELECT *
+FROM post
+WHERE published = false
+AND queued = true
+ORDER BY queue_position NULLS LAST, upload_date
+LIMIT 1;
+
+-- Functions
+CREATE OR REPLACE FUNCTION publish_next_post()
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH next AS (
+		SELECT n.id
+		FROM post AS n
+		WHERE n.published = false
+		AND n.queued = true
+		ORDER BY n.queue_position NULLS LAST, n.upload_date
+		LIMIT 1
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post AS p
+	SET published = true, queued = false, queue_position = NULL, publish_date = NOW()
+	WHERE p.id IN (SELECT next.id FROM next)
+	RETURNING p.id, p.slug;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,61 @@
+-- Functions
+DROP FUNCTION IF EXISTS tick_publish_queue();
+DROP FUNCTION IF EXISTS publish_next_post(text, text);
+
+CREATE FUNCTION publish_next_post()
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH next AS (
+		SELECT n.id
+		FROM post AS n
+		WHERE n.published = false
+		AND n.queued = true
+		ORDER BY n.queue_position NULLS LAST, n.upload_date
+		LIMIT 1
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post AS p
+	SET published = true, queued = false, queue_position = NULL, publish_date = NOW()
+	WHERE p.id IN (SELECT next.id FROM next)
+	RETURNING p.id, p.slug;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION tick_publish_queue()
+RETURNS TABLE(id integer, slug text)
+AS $$
+DECLARE
+	v_id int;
+BEGIN
+	WITH due AS (
+		SELECT pq.id, pq.run_interval
+		FROM publish_queue AS pq
+		WHERE pq.active = true
+		AND pq.next_run_at <= NOW()
+		ORDER BY pq.next_run_at
+		LIMIT 1
+		-- Only run once per date
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE publish_queue q
+	SET next_run_at = q.next_run_at + d.run_interval
+	FROM due d
+	WHERE q.id = d.id
+	RETURNING q.id
+	INTO v_id;
+
+	IF v_id IS NULL THEN
+		RETURN;
+	END IF;
+
+	RETURN QUERY
+	SELECT n.id, n.slug FROM publish_next_post() AS n;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Tables
+ALTER TABLE publish_queue
+	DROP COLUMN IF EXISTS category,
+	DROP COLUMN IF EXISTS tag_slug;
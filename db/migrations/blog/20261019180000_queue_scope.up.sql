@@ -0,0 +1,62 @@
+-- Tables
+-- Queues without a category or tag publish any queued post
+ALTER TABLE publish_queue
+	ADD COLUMN category text,
+	ADD COLUMN tag_slug text;
+
+-- Functions
+DROP FUNCTION IF EXISTS tick_publish_queue();
+DROP FUNCTION IF EXISTS publish_next_post();
+
+CREATE FUNCTION publish_next_post(p_category text DEFAULT NULL, p_tag_slug text DEFAULT NULL)
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH next AS (
+		SELECT n.id
+		FROM post AS n
+		WHERE n.published = false
+		AND n.queued = true
+		AND (p_category IS NULL OR n.category = p_category)
+		AND (p_tag_slug IS NULL OR EXISTS (
+			SELECT 1 FROM post_tag AS t
+			WHERE t.post_id = n.id
+			AND t.tag_slug = p_tag_slug
+		))
+		ORDER BY n.queue_position NULLS LAST, n.upload_date
+		LIMIT 1
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post AS p
+	SET published = true, queued = false, queue_position = NULL, publish_date = NOW()
+	WHERE p.id IN (SELECT next.id FROM next)
+	RETURNING p.id, p.slug;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Runs every due queue once, each publishing the next post that matches it
+CREATE FUNCTION tick_publish_queue()
+RETURNS TABLE(id integer, slug text)
+AS $$
+DECLARE
+	q record;
+BEGIN
+	FOR q IN
+		SELECT pq.id, pq.category, pq.tag_slug
+		FROM publish_queue AS pq
+		WHERE pq.active = true
+		AND pq.next_run_at <= NOW()
+		ORDER BY pq.next_run_at
+		-- Only run once per date
+		FOR UPDATE SKIP LOCKED
+	LOOP
+		UPDATE publish_queue AS u
+		SET next_run_at = u.next_run_at + u.run_interval
+		WHERE u.id = q.id;
+
+		RETURN QUERY
+		SELECT n.id, n.slug FROM publish_next_post(q.category, q.tag_slug) AS n;
+	END LOOP;
+END;
+$$ LANGUAGE plpgsql;
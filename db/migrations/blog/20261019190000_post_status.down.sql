@@ -0,0 +1,151 @@
+-- Views
+DROP VIEW IF EXISTS unpublished_posts;
+DROP VIEW IF EXISTS next_queued_post;
+
+-- Tables
+ALTER TABLE post
+ADD published boolean not null default false,
+ADD queued boolean not null default false;
+
+UPDATE post
+SET published = (status = 'published'),
+	queued = (status = 'queued');
+
+DROP TABLE IF EXISTS post_status_change;
+DROP INDEX IF EXISTS post_status_idx;
+
+ALTER TABLE post
+DROP COLUMN status;
+
+CREATE VIEW unpublished_posts
+AS
+SELECT *
+FROM post
+WHERE publish_date IS NOT NULL
+AND publish_date < CURRENT_TIMESTAMP
+AND published = false;
+
+CREATE VIEW next_queued_post
+AS
+SELECT *
+FROM post
+WHERE published = false
+AND queued = true
+ORDER BY queue_position NULLS LAST, upload_date
+LIMIT 1;
+
+-- Types
+DROP TYPE IF EXISTS post_status;
+
+-- Functions
+CREATE OR REPLACE FUNCTION get_author_posts(view_author integer)
+RETURNS TABLE(id integer,
+	slug text,
+	title text,
+	category text,
+	publish_date timestamp without time zone,
+	word_count integer,
+	reading_time integer,
+	excerpt text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT p.id, p.slug, p.title, p.category, p.publish_date,
+		p.word_count, p.reading_time, p.excerpt
+	FROM post AS p
+	WHERE p.published = true
+	AND (p.author_id = view_author
+		OR EXISTS (
+			SELECT 1
+			FROM post_author AS a
+			WHERE a.post_id = p.id
+			AND a.user_id = view_author
+		))
+	ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION get_series_nav(view_post integer)
+RETURNS TABLE(series_slug text,
+	series_title text,
+	position bigint,
+	total bigint,
+	prev_slug text,
+	prev_title text,
+	next_slug text,
+	next_title text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH parts AS (
+		SELECT p.id,
+			p.series_id,
+			ROW_NUMBER() OVER w AS part_position,
+			COUNT(*) OVER () AS part_total,
+			LAG(p.slug) OVER w AS part_prev_slug,
+			LAG(p.title) OVER w AS part_prev_title,
+			LEAD(p.slug) OVER w AS part_next_slug,
+			LEAD(p.title) OVER w AS part_next_title
+		FROM post AS p
+		WHERE p.published = true
+		AND p.series_id = (SELECT v.series_id FROM post AS v WHERE v.id = view_post)
+		WINDOW w AS (ORDER BY p.series_order, p.id)
+	)
+	SELECT s.slug, s.title,
+		parts.part_position, parts.part_total,
+		parts.part_prev_slug, parts.part_prev_title,
+		parts.part_next_slug, parts.part_next_title
+	FROM parts
+	JOIN series AS s ON s.id = parts.series_id
+	WHERE parts.id = view_post;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION publish_due_posts()
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH due AS (
+		SELECT d.id
+		FROM post AS d
+		WHERE d.publish_date IS NOT NULL
+		AND d.publish_date <= NOW()
+		AND d.published = false
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post AS p
+	SET published = true, queued = false
+	WHERE p.id IN (SELECT due.id FROM due)
+	RETURNING p.id, p.slug;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION publish_next_post(p_category text DEFAULT NULL, p_tag_slug text DEFAULT NULL)
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH next AS (
+		SELECT n.id
+		FROM post AS n
+		WHERE n.published = false
+		AND n.queued = true
+		AND (p_category IS NULL OR n.category = p_category)
+		AND (p_tag_slug IS NULL OR EXISTS (
+			SELECT 1 FROM post_tag AS t
+			WHERE t.post_id = n.id
+			AND t.tag_slug = p_tag_slug
+		))
+		ORDER BY n.queue_position NULLS LAST, n.upload_date
+		LIMIT 1
+		FOR UPDATE SKIP LOCKED
+	)
+	UPDATE post AS p
+	SET published = true, queued = false, queue_position = NULL, publish_date = NOW()
+	WHERE p.id IN (SELECT next.id FROM next)
+	RETURNING p.id, p.slug;
+END;
+$$ LANGUAGE plpgsql;
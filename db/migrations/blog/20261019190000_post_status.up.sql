@@ -0,0 +1,198 @@
+-- Types
+CREATE TYPE post_status AS ENUM (
+	'draft',
+	'queued',
+	'scheduled',
+	'published',
+	'archived',
+	'trashed'
+);
+
+-- Tables
+ALTER TABLE post
+ADD status post_status not null default 'draft';
+
+UPDATE post
+SET status = CASE
+	WHEN published THEN 'published'::post_status
+	WHEN publish_date IS NOT NULL THEN 'scheduled'::post_status
+	WHEN queued THEN 'queued'::post_status
+	ELSE 'draft'::post_status
+END;
+
+UPDATE post
+SET queue_position = NULL
+WHERE status <> 'queued';
+
+CREATE INDEX post_status_idx ON post (status);
+
+-- Who moved a post between statuses and when. A null user is the scheduler.
+CREATE TABLE post_status_change (
+	id serial,
+	post_id integer not null,
+	from_status post_status,
+	to_status post_status not null,
+	changed_by integer,
+	changed_at timestamp without time zone not null default NOW(),
+	PRIMARY KEY (id),
+	FOREIGN KEY (post_id) REFERENCES post(id)
+		ON UPDATE CASCADE
+		ON DELETE CASCADE,
+	FOREIGN KEY (changed_by) REFERENCES users(id)
+		ON UPDATE CASCADE
+		ON DELETE SET NULL
+);
+
+CREATE INDEX post_status_change_post_id ON post_status_change (post_id);
+
+INSERT INTO post_status_change (post_id, from_status, to_status, changed_at)
+SELECT id, NULL, status, upload_date
+FROM post;
+
+-- Views
+-- Views select every column, so they have to go before the old columns do
+DROP VIEW IF EXISTS unpublished_posts;
+DROP VIEW IF EXISTS next_queued_post;
+
+ALTER TABLE post
+DROP COLUMN published,
+DROP COLUMN queued;
+
+CREATE VIEW unpublished_posts
+AS
+SELECT *
+FROM post
+WHERE status = 'scheduled'
+AND publish_date < CURRENT_TIMESTAMP;
+
+CREATE VIEW next_queued_post
+AS
+SELECT *
+FROM post
+WHERE status = 'queued'
+ORDER BY queue_position NULLS LAST, upload_date
+LIMIT 1;
+
+-- Functions
+CREATE OR REPLACE FUNCTION get_author_posts(view_author integer)
+RETURNS TABLE(id integer,
+	slug text,
+	title text,
+	category text,
+	publish_date timestamp without time zone,
+	word_count integer,
+	reading_time integer,
+	excerpt text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT p.id, p.slug, p.title, p.category, p.publish_date,
+		p.word_count, p.reading_time, p.excerpt
+	FROM post AS p
+	WHERE p.status = 'published'
+	AND (p.author_id = view_author
+		OR EXISTS (
+			SELECT 1
+			FROM post_author AS a
+			WHERE a.post_id = p.id
+			AND a.user_id = view_author
+		))
+	ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION get_series_nav(view_post integer)
+RETURNS TABLE(series_slug text,
+	series_title text,
+	position bigint,
+	total bigint,
+	prev_slug text,
+	prev_title text,
+	next_slug text,
+	next_title text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH parts AS (
+		SELECT p.id,
+			p.series_id,
+			ROW_NUMBER() OVER w AS part_position,
+			COUNT(*) OVER () AS part_total,
+			LAG(p.slug) OVER w AS part_prev_slug,
+			LAG(p.title) OVER w AS part_prev_title,
+			LEAD(p.slug) OVER w AS part_next_slug,
+			LEAD(p.title) OVER w AS part_next_title
+		FROM post AS p
+		WHERE p.status = 'published'
+		AND p.series_id = (SELECT v.series_id FROM post AS v WHERE v.id = view_post)
+		WINDOW w AS (ORDER BY p.series_order, p.id)
+	)
+	SELECT s.slug, s.title,
+		parts.part_position, parts.part_total,
+		parts.part_prev_slug, parts.part_prev_title,
+		parts.part_next_slug, parts.part_next_title
+	FROM parts
+	JOIN series AS s ON s.id = parts.series_id
+	WHERE parts.id = view_post;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Publishing functions record their status changes with no user
+CREATE OR REPLACE FUNCTION publish_due_posts()
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH due AS (
+		SELECT d.id
+		FROM post AS d
+		WHERE d.status = 'scheduled'
+		AND d.publish_date <= NOW()
+		FOR UPDATE SKIP LOCKED
+	), moved AS (
+		UPDATE post AS p
+		SET status = 'published'
+		WHERE p.id IN (SELECT due.id FROM due)
+		RETURNING p.id, p.slug
+	), logged AS (
+		INSERT INTO post_status_change (post_id, from_status, to_status)
+		SELECT moved.id, 'scheduled', 'published'
+		FROM moved
+	)
+	SELECT moved.id, moved.slug FROM moved;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION publish_next_post(p_category text DEFAULT NULL, p_tag_slug text DEFAULT NULL)
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH next AS (
+		SELECT n.id
+		FROM post AS n
+		WHERE n.status = 'queued'
+		AND (p_category IS NULL OR n.category = p_category)
+		AND (p_tag_slug IS NULL OR EXISTS (
+			SELECT 1 FROM post_tag AS t
+			WHERE t.post_id = n.id
+			AND t.tag_slug = p_tag_slug
+		))
+		ORDER BY n.queue_position NULLS LAST, n.upload_date
+		LIMIT 1
+		FOR UPDATE SKIP LOCKED
+	), moved AS (
+		UPDATE post AS p
+		SET status = 'published', queue_position = NULL, publish_date = NOW()
+		WHERE p.id IN (SELECT next.id FROM next)
+		RETURNING p.id, p.slug
+	), logged AS (
+		INSERT INTO post_status_change (post_id, from_status, to_status)
+		SELECT moved.id, 'queued', 'published'
+		FROM moved
+	)
+	SELECT moved.id, moved.slug FROM moved;
+END;
+$$ LANGUAGE plpgsql;
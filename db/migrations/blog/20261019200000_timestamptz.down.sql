@@ -0,0 +1,87 @@
+-- Views
+-- Views select every column, so they have to go before the column types can change
+DROP VIEW IF EXISTS unpublished_posts;
+DROP VIEW IF EXISTS next_queued_post;
+
+-- Tables
+-- Values are converted to the server time zone
+ALTER TABLE post
+ALTER COLUMN upload_date TYPE timestamp without time zone,
+ALTER COLUMN publish_date TYPE timestamp without time zone;
+
+ALTER TABLE comments
+ALTER COLUMN created_at TYPE timestamp without time zone,
+ALTER COLUMN updated_at TYPE timestamp without time zone;
+
+ALTER TABLE updated_post
+ALTER COLUMN last_updated TYPE timestamp without time zone;
+
+ALTER TABLE publish_queue
+ALTER COLUMN next_run_at TYPE timestamp without time zone;
+
+ALTER TABLE post_status_change
+ALTER COLUMN changed_at TYPE timestamp without time zone;
+
+CREATE VIEW unpublished_posts
+AS
+SELECT *
+FROM post
+WHERE status = 'scheduled'
+AND publish_date < CURRENT_TIMESTAMP;
+
+CREATE VIEW next_queued_post
+AS
+SELECT *
+FROM post
+WHERE status = 'queued'
+ORDER BY queue_position NULLS LAST, upload_date
+LIMIT 1;
+
+-- Functions
+-- Return types change, so the functions have to be recreated
+DROP FUNCTION IF EXISTS get_author_posts(integer);
+CREATE FUNCTION get_author_posts(view_author integer)
+RETURNS TABLE(id integer,
+	slug text,
+	title text,
+	category text,
+	publish_date timestamp without time zone,
+	word_count integer,
+	reading_time integer,
+	excerpt text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT p.id, p.slug, p.title, p.category, p.publish_date,
+		p.word_count, p.reading_time, p.excerpt
+	FROM post AS p
+	WHERE p.status = 'published'
+	AND (p.author_id = view_author
+		OR EXISTS (
+			SELECT 1
+			FROM post_author AS a
+			WHERE a.post_id = p.id
+			AND a.user_id = view_author
+		))
+	ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP FUNCTION IF EXISTS get_comments(integer);
+CREATE FUNCTION get_comments(view_post integer)
+RETURNS TABLE(username text,
+	body text,
+	created_at timestamp without time zone,
+	updated_at timestamp without time zone
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT u.username, c.body, c.created_at, c.updated_at
+	FROM comments AS c
+	LEFT JOIN users AS u ON c.user_id = u.id
+	WHERE c.post_id = view_post
+	ORDER BY c.created_at;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,7 @@
+-- Functions
+DROP FUNCTION IF EXISTS archive_expired_posts();
+
+-- Tables
+ALTER TABLE post
+DROP COLUMN IF EXISTS expires_at,
+DROP COLUMN IF EXISTS review_by;
@@ -0,0 +1,31 @@
+-- Tables
+ALTER TABLE post
+ADD expires_at timestamptz,
+ADD review_by timestamptz;
+
+-- Functions
+-- Archives published posts whose expiry date has passed
+CREATE FUNCTION archive_expired_posts()
+RETURNS TABLE(id integer, slug text)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH expired AS (
+		SELECT e.id
+		FROM post AS e
+		WHERE e.status = 'published'
+		AND e.expires_at <= NOW()
+		FOR UPDATE SKIP LOCKED
+	), moved AS (
+		UPDATE post AS p
+		SET status = 'archived'
+		WHERE p.id IN (SELECT expired.id FROM expired)
+		RETURNING p.id, p.slug
+	), logged AS (
+		INSERT INTO post_status_change (post_id, from_status, to_status)
+		SELECT moved.id, 'published', 'archived'
+		FROM moved
+	)
+	SELECT moved.id, moved.slug FROM moved;
+END;
+$$ LANGUAGE plpgsql;
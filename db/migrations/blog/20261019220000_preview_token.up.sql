@@ -0,0 +1,20 @@
+-- Tables
+-- Signed draft preview links. Only the ID is stored; the token itself is a JWT.
+CREATE TABLE preview_token (
+	id serial,
+	post_id integer not null,
+	label text,
+	created_by integer,
+	created_at timestamptz not null default NOW(),
+	expires_at timestamptz not null,
+	revoked_at timestamptz,
+	PRIMARY KEY (id),
+	FOREIGN KEY (post_id) REFERENCES post(id)
+		ON UPDATE CASCADE
+		ON DELETE CASCADE,
+	FOREIGN KEY (created_by) REFERENCES users(id)
+		ON UPDATE CASCADE
+		ON DELETE SET NULL
+);
+
+CREATE INDEX preview_token_post_id ON preview_token (post_id);
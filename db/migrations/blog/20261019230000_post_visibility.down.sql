@@ -0,0 +1,75 @@
+-- Functions
+DROP FUNCTION IF EXISTS get_author_posts(integer, user_role[]);
+CREATE FUNCTION get_author_posts(view_author integer)
+RETURNS TABLE(id integer,
+	slug text,
+	title text,
+	category text,
+	publish_date timestamptz,
+	word_count integer,
+	reading_time integer,
+	excerpt text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT p.id, p.slug, p.title, p.category, p.publish_date,
+		p.word_count, p.reading_time, p.excerpt
+	FROM post AS p
+	WHERE p.status = 'published'
+	AND (p.author_id = view_author
+		OR EXISTS (
+			SELECT 1
+			FROM post_author AS a
+			WHERE a.post_id = p.id
+			AND a.user_id = view_author
+		))
+	ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP FUNCTION IF EXISTS get_series_nav(integer, user_role[]);
+CREATE FUNCTION get_series_nav(view_post integer)
+RETURNS TABLE(series_slug text,
+	series_title text,
+	position bigint,
+	total bigint,
+	prev_slug text,
+	prev_title text,
+	next_slug text,
+	next_title text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH parts AS (
+		SELECT p.id,
+			p.series_id,
+			ROW_NUMBER() OVER w AS part_position,
+			COUNT(*) OVER () AS part_total,
+			LAG(p.slug) OVER w AS part_prev_slug,
+			LAG(p.title) OVER w AS part_prev_title,
+			LEAD(p.slug) OVER w AS part_next_slug,
+			LEAD(p.title) OVER w AS part_next_title
+		FROM post AS p
+		WHERE p.status = 'published'
+		AND p.series_id = (SELECT v.series_id FROM post AS v WHERE v.id = view_post)
+		WINDOW w AS (ORDER BY p.series_order, p.id)
+	)
+	SELECT s.slug, s.title,
+		parts.part_position, parts.part_total,
+		parts.part_prev_slug, parts.part_prev_title,
+		parts.part_next_slug, parts.part_next_title
+	FROM parts
+	JOIN series AS s ON s.id = parts.series_id
+	WHERE parts.id = view_post;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Tables
+ALTER TABLE post
+DROP COLUMN IF EXISTS visibility,
+DROP COLUMN IF EXISTS min_role;
+
+-- Types
+DROP TYPE IF EXISTS post_visibility;
@@ -0,0 +1,87 @@
+-- Types
+CREATE TYPE post_visibility AS ENUM (
+	'public',
+	'unlisted',
+	'members'
+);
+
+-- Tables
+-- min_role only matters for members-only posts
+ALTER TABLE post
+ADD visibility post_visibility not null default 'public',
+ADD min_role user_role not null default 'guest';
+
+-- Functions
+-- Listing functions take the minimum roles the viewer satisfies
+DROP FUNCTION IF EXISTS get_author_posts(integer);
+CREATE FUNCTION get_author_posts(view_author integer, viewer_roles user_role[])
+RETURNS TABLE(id integer,
+	slug text,
+	title text,
+	category text,
+	publish_date timestamptz,
+	word_count integer,
+	reading_time integer,
+	excerpt text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT p.id, p.slug, p.title, p.category, p.publish_date,
+		p.word_count, p.reading_time, p.excerpt
+	FROM post AS p
+	WHERE p.status = 'published'
+	AND (p.visibility = 'public'
+		OR (p.visibility = 'members' AND p.min_role = ANY(viewer_roles)))
+	AND (p.author_id = view_author
+		OR EXISTS (
+			SELECT 1
+			FROM post_author AS a
+			WHERE a.post_id = p.id
+			AND a.user_id = view_author
+		))
+	ORDER BY p.publish_date DESC NULLS LAST, p.upload_date DESC;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Parts the viewer cannot find are left out, except the post being viewed
+DROP FUNCTION IF EXISTS get_series_nav(integer);
+CREATE FUNCTION get_series_nav(view_post integer, viewer_roles user_role[])
+RETURNS TABLE(series_slug text,
+	series_title text,
+	position bigint,
+	total bigint,
+	prev_slug text,
+	prev_title text,
+	next_slug text,
+	next_title text
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH parts AS (
+		SELECT p.id,
+			p.series_id,
+			ROW_NUMBER() OVER w AS part_position,
+			COUNT(*) OVER () AS part_total,
+			LAG(p.slug) OVER w AS part_prev_slug,
+			LAG(p.title) OVER w AS part_prev_title,
+			LEAD(p.slug) OVER w AS part_next_slug,
+			LEAD(p.title) OVER w AS part_next_title
+		FROM post AS p
+		WHERE (p.id = view_post
+			OR (p.status = 'published'
+				AND (p.visibility = 'public'
+					OR (p.visibility = 'members' AND p.min_role = ANY(viewer_roles)))))
+		AND p.series_id = (SELECT v.series_id FROM post AS v WHERE v.id = view_post)
+		WINDOW w AS (ORDER BY p.series_order, p.id)
+	)
+	SELECT s.slug, s.title,
+		parts.part_position, parts.part_total,
+		parts.part_prev_slug, parts.part_prev_title,
+		parts.part_next_slug, parts.part_next_title
+	FROM parts
+	JOIN series AS s ON s.id = parts.series_id
+	WHERE parts.id = view_post;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,3 @@
+-- Tables
+ALTER TABLE post
+DROP COLUMN IF EXISTS password_hash;
@@ -0,0 +1,4 @@
+-- Tables
+-- Argon2 hash of the shared password of a protected post
+ALTER TABLE post
+ADD password_hash text;
@@ -0,0 +1,18 @@
+-- Functions
+DROP FUNCTION IF EXISTS get_comments(integer);
+CREATE FUNCTION get_comments(view_post integer)
+RETURNS TABLE(username text,
+	body text,
+	created_at timestamptz,
+	updated_at timestamptz
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT u.username, c.body, c.created_at, c.updated_at
+	FROM comments AS c
+	LEFT JOIN users AS u ON c.user_id = u.id
+	WHERE c.post_id = view_post
+	ORDER BY c.created_at;
+END;
+$$ LANGUAGE plpgsql;
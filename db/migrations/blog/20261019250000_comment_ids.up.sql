@@ -0,0 +1,21 @@
+-- Functions
+-- Return type changes, so the function has to be recreated
+DROP FUNCTION IF EXISTS get_comments(integer);
+CREATE FUNCTION get_comments(view_post integer)
+RETURNS TABLE(id integer,
+	user_id integer,
+	username text,
+	body text,
+	created_at timestamptz,
+	updated_at timestamptz
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT c.id, c.user_id, u.username, c.body, c.created_at, c.updated_at
+	FROM comments AS c
+	LEFT JOIN users AS u ON c.user_id = u.id
+	WHERE c.post_id = view_post
+	ORDER BY c.created_at, c.id;
+END;
+$$ LANGUAGE plpgsql;
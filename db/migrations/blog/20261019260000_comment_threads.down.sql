@@ -0,0 +1,30 @@
+-- Functions
+DROP FUNCTION IF EXISTS get_comments(integer);
+CREATE FUNCTION get_comments(view_post integer)
+RETURNS TABLE(id integer,
+	user_id integer,
+	username text,
+	body text,
+	created_at timestamptz,
+	updated_at timestamptz
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	SELECT c.id, c.user_id, u.username, c.body, c.created_at, c.updated_at
+	FROM comments AS c
+	LEFT JOIN users AS u ON c.user_id = u.id
+	WHERE c.post_id = view_post
+	ORDER BY c.created_at, c.id;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP FUNCTION IF EXISTS comment_depth(integer);
+
+-- Tables
+-- Placeholders for deleted comments are dropped along with their replies
+DELETE FROM comments WHERE deleted;
+DROP INDEX IF EXISTS comments_parent_id_idx;
+ALTER TABLE comments
+	DROP COLUMN deleted,
+	DROP COLUMN parent_id;
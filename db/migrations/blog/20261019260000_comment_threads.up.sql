@@ -0,0 +1,56 @@
+-- Tables
+ALTER TABLE comments
+	ADD COLUMN parent_id integer REFERENCES comments(id) ON DELETE CASCADE,
+	ADD COLUMN deleted boolean NOT NULL DEFAULT false;
+CREATE INDEX comments_parent_id_idx ON comments (parent_id);
+
+-- Functions
+-- Number of ancestors above a comment. Top-level comments are depth 0.
+CREATE OR REPLACE FUNCTION comment_depth(comment integer)
+RETURNS integer
+AS $$
+	WITH RECURSIVE ancestors AS (
+		SELECT c.parent_id FROM comments AS c WHERE c.id = comment
+		UNION ALL
+		SELECT c.parent_id
+		FROM comments AS c
+		JOIN ancestors AS a ON c.id = a.parent_id
+	)
+	SELECT (count(*) - 1)::integer FROM ancestors;
+$$ LANGUAGE sql STABLE;
+
+-- Return type changes, so the function has to be recreated.
+-- Replies follow their parent, siblings are ordered oldest first.
+DROP FUNCTION IF EXISTS get_comments(integer);
+CREATE FUNCTION get_comments(view_post integer)
+RETURNS TABLE(id integer,
+	parent_id integer,
+	depth integer,
+	user_id integer,
+	username text,
+	body text,
+	deleted boolean,
+	created_at timestamptz,
+	updated_at timestamptz
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH RECURSIVE thread AS (
+		SELECT c.id, 0 AS depth, ARRAY[c.id] AS path
+		FROM comments AS c
+		WHERE c.post_id = view_post AND c.parent_id IS NULL
+		UNION ALL
+		SELECT c.id, t.depth + 1, t.path || c.id
+		FROM comments AS c
+		JOIN thread AS t ON c.parent_id = t.id
+	)
+	SELECT c.id, c.parent_id, t.depth, c.user_id, u.username,
+		CASE WHEN c.deleted THEN '[deleted]' ELSE c.body END,
+		c.deleted, c.created_at, c.updated_at
+	FROM thread AS t
+	JOIN comments AS c ON c.id = t.id
+	LEFT JOIN users AS u ON c.user_id = u.id
+	ORDER BY t.path;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,37 @@
+-- Functions
+DROP FUNCTION IF EXISTS get_comments(integer);
+CREATE FUNCTION get_comments(view_post integer)
+RETURNS TABLE(id integer,
+	parent_id integer,
+	depth integer,
+	user_id integer,
+	username text,
+	body text,
+	deleted boolean,
+	created_at timestamptz,
+	updated_at timestamptz
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH RECURSIVE thread AS (
+		SELECT c.id, 0 AS depth, ARRAY[c.id] AS path
+		FROM comments AS c
+		WHERE c.post_id = view_post AND c.parent_id IS NULL
+		UNION ALL
+		SELECT c.id, t.depth + 1, t.path || c.id
+		FROM comments AS c
+		JOIN thread AS t ON c.parent_id = t.id
+	)
+	SELECT c.id, c.parent_id, t.depth, c.user_id, u.username,
+		CASE WHEN c.deleted THEN '[deleted]' ELSE c.body END,
+		c.deleted, c.created_at, c.updated_at
+	FROM thread AS t
+	JOIN comments AS c ON c.id = t.id
+	LEFT JOIN users AS u ON c.user_id = u.id
+	ORDER BY t.path;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Tables
+ALTER TABLE comments DROP COLUMN body_html;
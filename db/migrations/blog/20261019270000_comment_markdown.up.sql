@@ -0,0 +1,46 @@
+-- Tables
+-- `body` keeps the markdown source for editing, `body_html` the sanitized rendering
+ALTER TABLE comments ADD COLUMN body_html text NOT NULL DEFAULT '';
+-- Existing comments were plain text; escape them until they are next edited
+UPDATE comments
+SET body_html = '<p>' || replace(replace(replace(body, '&', '&amp;'), '<', '&lt;'), '>', '&gt;') || '</p>'
+WHERE NOT deleted;
+ALTER TABLE comments ALTER COLUMN body_html DROP DEFAULT;
+
+-- Functions
+-- Return type changes, so the function has to be recreated.
+DROP FUNCTION IF EXISTS get_comments(integer);
+CREATE FUNCTION get_comments(view_post integer)
+RETURNS TABLE(id integer,
+	parent_id integer,
+	depth integer,
+	user_id integer,
+	username text,
+	body text,
+	body_html text,
+	deleted boolean,
+	created_at timestamptz,
+	updated_at timestamptz
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH RECURSIVE thread AS (
+		SELECT c.id, 0 AS depth, ARRAY[c.id] AS path
+		FROM comments AS c
+		WHERE c.post_id = view_post AND c.parent_id IS NULL
+		UNION ALL
+		SELECT c.id, t.depth + 1, t.path || c.id
+		FROM comments AS c
+		JOIN thread AS t ON c.parent_id = t.id
+	)
+	SELECT c.id, c.parent_id, t.depth, c.user_id, u.username,
+		CASE WHEN c.deleted THEN '[deleted]' ELSE c.body END,
+		CASE WHEN c.deleted THEN '<p>[deleted]</p>' ELSE c.body_html END,
+		c.deleted, c.created_at, c.updated_at
+	FROM thread AS t
+	JOIN comments AS c ON c.id = t.id
+	LEFT JOIN users AS u ON c.user_id = u.id
+	ORDER BY t.path;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,54 @@
+-- Types
+CREATE TYPE comment_status AS ENUM ('pending', 'approved', 'spam', 'rejected');
+
+-- Tables
+-- Comments made before moderation existed stay visible
+ALTER TABLE comments ADD COLUMN status comment_status NOT NULL DEFAULT 'approved';
+ALTER TABLE comments ALTER COLUMN status SET DEFAULT 'pending';
+CREATE INDEX comments_status_idx ON comments (status);
+
+-- Functions
+-- Only approved comments are listed, plus the viewer's own pending ones.
+-- Replies under a hidden comment are hidden with it.
+DROP FUNCTION IF EXISTS get_comments(integer);
+CREATE FUNCTION get_comments(view_post integer, viewer integer DEFAULT NULL)
+RETURNS TABLE(id integer,
+	post_id integer,
+	parent_id integer,
+	depth integer,
+	user_id integer,
+	username text,
+	body text,
+	body_html text,
+	status comment_status,
+	deleted boolean,
+	created_at timestamptz,
+	updated_at timestamptz
+)
+AS $$
+BEGIN
+	RETURN QUERY
+	WITH RECURSIVE visible AS (
+		SELECT c.*
+		FROM comments AS c
+		WHERE c.post_id = view_post
+			AND (c.status = 'approved' OR (c.status = 'pending' AND c.user_id = viewer))
+	), thread AS (
+		SELECT v.id, 0 AS depth, ARRAY[v.id] AS path
+		FROM visible AS v
+		WHERE v.parent_id IS NULL
+		UNION ALL
+		SELECT v.id, t.depth + 1, t.path || v.id
+		FROM visible AS v
+		JOIN thread AS t ON v.parent_id = t.id
+	)
+	SELECT c.id, c.post_id, c.parent_id, t.depth, c.user_id, u.username,
+		CASE WHEN c.deleted THEN '[deleted]' ELSE c.body END,
+		CASE WHEN c.deleted THEN '<p>[deleted]</p>' ELSE c.body_html END,
+		c.status, c.deleted, c.created_at, c.updated_at
+	FROM thread AS t
+	JOIN comments AS c ON c.id = t.id
+	LEFT JOIN users AS u ON c.user_id = u.id
+	ORDER BY t.path;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,2 @@
+-- Tables
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_username_key;
@@ -0,0 +1,10 @@
+-- Tables
+-- Usernames name authors in front matter and URLs, so they have to be unique.
+-- Later duplicates are renamed rather than dropped, keeping the first account's name.
+UPDATE users AS u
+SET username = u.username || '-' || u.id
+WHERE EXISTS (
+	SELECT 1 FROM users AS o
+	WHERE o.username = u.username AND o.id < u.id
+);
+ALTER TABLE users ADD CONSTRAINT users_username_key UNIQUE (username);
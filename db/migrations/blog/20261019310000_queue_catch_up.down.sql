@@ -0,0 +1,27 @@
+-- Functions
+CREATE OR REPLACE FUNCTION tick_publish_queue()
+RETURNS TABLE(id integer, slug text)
+AS $$
+DECLARE
+	q record;
+BEGIN
+	FOR q IN
+		SELECT pq.id, pq.category, pq.tag_slug
+		FROM publish_queue AS pq
+		WHERE pq.active = true
+		AND pq.next_run_at <= NOW()
+		ORDER BY pq.next_run_at
+		-- Only run once per date
+		FOR UPDATE SKIP LOCKED
+	LOOP
+		UPDATE publish_queue AS u
+		SET next_run_at = u.next_run_at + u.run_interval
+		WHERE u.id = q.id;
+
+		RETURN QUERY
+		SELECT n.id, n.slug FROM publish_next_post(q.category, q.tag_slug) AS n;
+	END LOOP;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP FUNCTION IF EXISTS next_queue_run(timestamptz, interval);
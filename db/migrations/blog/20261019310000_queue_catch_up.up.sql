@@ -0,0 +1,41 @@
+-- Functions
+-- First run of a queue after NOW(), so a queue that was paused or missed ticks skips the runs
+-- it missed instead of catching up on them one tick at a time
+CREATE FUNCTION next_queue_run(p_next_run_at timestamptz, p_run_interval interval)
+RETURNS timestamptz
+AS $$
+BEGIN
+	IF p_next_run_at > NOW() THEN
+		RETURN p_next_run_at;
+	END IF;
+	RETURN p_next_run_at + (
+		floor(EXTRACT(EPOCH FROM NOW() - p_next_run_at) / EXTRACT(EPOCH FROM p_run_interval)) + 1
+	)::integer * p_run_interval;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Runs every due queue once, each publishing the next post that matches it
+CREATE OR REPLACE FUNCTION tick_publish_queue()
+RETURNS TABLE(id integer, slug text)
+AS $$
+DECLARE
+	q record;
+BEGIN
+	FOR q IN
+		SELECT pq.id, pq.category, pq.tag_slug
+		FROM publish_queue AS pq
+		WHERE pq.active = true
+		AND pq.next_run_at <= NOW()
+		ORDER BY pq.next_run_at
+		-- Only run once per date
+		FOR UPDATE SKIP LOCKED
+	LOOP
+		UPDATE publish_queue AS u
+		SET next_run_at = next_queue_run(u.next_run_at, u.run_interval)
+		WHERE u.id = q.id;
+
+		RETURN QUERY
+		SELECT n.id, n.slug FROM publish_next_post(q.category, q.tag_slug) AS n;
+	END LOOP;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,10 @@
+-- Tables
+-- Proof-of-work challenges that were already used. Challenges are signed, so only their `sub`
+-- needs keeping, and only until they expire.
+CREATE TABLE spent_challenge (
+	sub text,
+	expires_at timestamptz not null,
+	PRIMARY KEY (sub)
+);
+
+CREATE INDEX spent_challenge_expires_at ON spent_challenge (expires_at);
@@ -0,0 +1,2 @@
+-- Tables
+DROP TABLE IF EXISTS contact_message;
@@ -0,0 +1,10 @@
+-- Tables
+-- Messages sent through the contact form
+CREATE TABLE contact_message (
+	id serial,
+	name text not null,
+	email text not null,
+	message text not null,
+	created_at timestamptz not null default NOW(),
+	PRIMARY KEY (id)
+);
@@ -0,0 +1,8 @@
+-- pg_cron
+DO $$
+BEGIN
+	IF EXISTS (SELECT 1 FROM pg_catalog.pg_tables WHERE tablename = 'job' AND schemaname = 'cron') THEN
+		PERFORM cron.schedule('publish-queue-tick', '* * * * *', $cmd$SELECT tick_publish_queue();$cmd$);
+	END IF;
+END;
+$$;
@@ -0,0 +1,12 @@
+-- pg_cron
+-- The server's scheduler ticks the publish queues now; running the tick here as well would
+-- publish posts twice as fast
+DO $$
+BEGIN
+	IF EXISTS (SELECT 1 FROM pg_catalog.pg_tables WHERE tablename = 'job' AND schemaname = 'cron') THEN
+		PERFORM cron.unschedule(jobid)
+		FROM cron.job
+		WHERE jobname =	'publish-queue-tick';
+	END IF;
+END;
+$$;